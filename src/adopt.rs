@@ -0,0 +1,175 @@
+use crate::fs::{move_path, remove_symlink, symlink};
+use crate::overflow;
+use crate::path::{file_name, MaybePath};
+use crate::{Corpus, CorpusError};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+impl Corpus {
+    /// Moves the `name` file or directory (e.g. `.venv`) within the `input`
+    /// source directory to the directory's entry, and returns the entry.
+    ///
+    /// With `link`, a symlink to the entry is left in its place. An existing
+    /// entry is never overwritten, and `name` must be a single file name, so
+    /// that nothing outside of the source directory is moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let source = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to(source.path())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let venv = source.path().join("project/.venv");
+    /// std::fs::create_dir_all(&venv).unwrap();
+    ///
+    /// let project = source.path().join("project");
+    /// let entry = corpus.adopt(project.as_path(), ".venv", true).unwrap();
+    /// assert_eq!(entry, root.path().join("project"));
+    /// assert_eq!(std::fs::read_link(&venv).unwrap(), entry);
+    /// # }
+    /// ```
+    pub fn adopt<'a, I: Into<MaybePath<'a>>, N: AsRef<Path>>(
+        &self,
+        input: I,
+        name: N,
+        link: bool,
+    ) -> Result<PathBuf, CorpusError> {
+        let source = self.source(input);
+        let local = source.join(file_name(name.as_ref())?);
+        let entry = self.path(source.as_path());
+        let refuse = |reason| CorpusError::Move {
+            from: local.clone(),
+            to: entry.clone(),
+            reason,
+        };
+
+        match std::fs::symlink_metadata(&local) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(refuse("it is a symlink, not an in-tree file"))
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(refuse("it doesn't exist")),
+            Err(e) => return Err(e.into()),
+        }
+        if std::fs::symlink_metadata(&entry).is_ok() {
+            return Err(refuse("the entry already exists"));
+        }
+
+        self.prepare_path(source.as_path())?;
+        move_path(&local, &entry)?;
+        if link {
+            symlink(&entry, &local)?;
+        }
+        Ok(entry)
+    }
+
+    /// Moves the entry for the `input` source directory back into it, as its
+    /// `name` file or directory, reversing [`Corpus::adopt`]. Returns the new
+    /// in-tree path.
+    ///
+    /// A symlink to the entry at `name` is replaced, but nothing else is
+    /// overwritten.
+    pub fn unadopt<'a, I: Into<MaybePath<'a>>, N: AsRef<Path>>(
+        &self,
+        input: I,
+        name: N,
+    ) -> Result<PathBuf, CorpusError> {
+        let source = self.source(input);
+        let local = source.join(file_name(name.as_ref())?);
+        let entry = self.path(source.as_path());
+        let refuse = |reason| CorpusError::Move {
+            from: entry.clone(),
+            to: local.clone(),
+            reason,
+        };
+
+        if !entry.exists() {
+            return Err(refuse("the entry doesn't exist"));
+        }
+        match std::fs::symlink_metadata(&local) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                if std::fs::read_link(&local)? != entry {
+                    return Err(refuse("the destination is a symlink to something else"));
+                }
+                remove_symlink(&local)?;
+            }
+            Ok(_) => return Err(refuse("the destination already exists")),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        move_path(&entry, &local)?;
+        Ok(local)
+    }
+
+    /// Moves the entry for the `from` source path to be the entry for the
+    /// `to` source path (e.g. after the source directory itself was moved),
+    /// and returns the moved entry. The source paths are left alone.
+    ///
+    /// The entry is copied (and then removed) if it crosses filesystems. The
+    /// old entry's lock file and overflow sidecar are removed, and recorded
+    /// links (see [`Corpus::link`]) to it are moved to `to` and pointed at the
+    /// moved entry. An existing entry is never overwritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let source = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to(source.path())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// std::fs::create_dir_all(root.path().join("old")).unwrap();
+    /// let (old, new) = (source.path().join("old"), source.path().join("new"));
+    /// let entry = corpus.move_entry(old.as_path(), new.as_path()).unwrap();
+    /// assert_eq!(entry, root.path().join("new"));
+    /// assert!(entry.is_dir());
+    /// ```
+    pub fn move_entry<'a, 'b, F: Into<MaybePath<'a>>, T: Into<MaybePath<'b>>>(
+        &self,
+        from: F,
+        to: T,
+    ) -> Result<PathBuf, CorpusError> {
+        let (from_source, to_source) = (self.source(from), self.source(to));
+        let from = self.path(from_source.as_path());
+        let to = self.path(to_source.as_path());
+        let refuse = |reason| CorpusError::Move {
+            from: from.clone(),
+            to: to.clone(),
+            reason,
+        };
+
+        if std::fs::symlink_metadata(&from).is_err() {
+            return Err(refuse("the entry doesn't exist"));
+        }
+        if std::fs::symlink_metadata(&to).is_ok() {
+            return Err(refuse("the destination entry already exists"));
+        }
+
+        self.prepare_path(to_source.as_path())?;
+        move_path(&from, &to)?;
+        self.remove_locks(&from)?;
+        if let Some(sidecar) = overflow::sidecar(&from) {
+            match std::fs::remove_file(sidecar) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        self.move_links(&from_source, &to_source, &from)?;
+        Ok(to)
+    }
+}
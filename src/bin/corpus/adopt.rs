@@ -0,0 +1,48 @@
+use crate::options::CorpusOptions;
+use crate::output::Printer;
+use clap::Clap;
+use std::path::PathBuf;
+
+#[derive(Clap, Debug)]
+pub struct AdoptCommand {
+    /// The file or directory within the source directory (e.g. `.venv`).
+    #[clap(parse(from_os_str))]
+    pub local_name: PathBuf,
+
+    /// Leaves a symlink to the entry in its place.
+    #[clap(long)]
+    pub link: bool,
+}
+
+#[derive(Clap, Debug)]
+pub struct UnadoptCommand {
+    /// The file or directory within the source directory to move the entry
+    /// back to, replacing any symlink to the entry.
+    #[clap(parse(from_os_str))]
+    pub local_name: PathBuf,
+}
+
+pub fn run_adopt(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &AdoptCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+    let entry = corpus.adopt(source.as_path(), &command.local_name, command.link)?;
+    printer.entry(&corpus, &entry, &source)?;
+    Ok(0)
+}
+
+pub fn run_unadopt(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &UnadoptCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+    let entry = corpus.path(source.as_path());
+    let local = corpus.unadopt(source.as_path(), &command.local_name)?;
+    printer.source(&corpus, &entry, &local)?;
+    Ok(0)
+}
@@ -0,0 +1,94 @@
+use crate::options::CorpusOptions;
+use crate::output::Printer;
+use clap::Clap;
+use corpus::EnsureOptions;
+use path_absolutize::Absolutize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Clap, Debug, Clone, Copy)]
+pub enum CreateAs {
+    Dir,
+    File,
+}
+
+impl FromStr for CreateAs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "dir" => Self::Dir,
+            "file" => Self::File,
+            _ => return Err("Invalid option".to_string()),
+        })
+    }
+}
+
+impl CreateAs {
+    pub fn options(self) -> EnsureOptions {
+        match self {
+            Self::Dir => EnsureOptions::new(),
+            Self::File => EnsureOptions::new().as_file(),
+        }
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct EnsureCommand {
+    /// Creates the entry as an empty file, rather than as a directory.
+    #[clap(long)]
+    pub file: bool,
+
+    /// The permissions of a created entry, in octal (e.g. `0700`).
+    #[clap(long, parse(try_from_str = parse_mode))]
+    pub mode: Option<u32>,
+
+    /// Creates the entry as a copy of this directory or file.
+    #[clap(long, parse(from_os_str))]
+    pub template: Option<PathBuf>,
+
+    /// A shell command which initializes a created entry, with `{}` standing
+    /// in for its path, quoted or not (e.g. `python -m venv {}`).
+    #[clap(long)]
+    pub init: Option<String>,
+}
+
+impl EnsureCommand {
+    pub fn options(&self) -> anyhow::Result<EnsureOptions> {
+        let create_as = if self.file {
+            CreateAs::File
+        } else {
+            CreateAs::Dir
+        };
+        let mut options = create_as.options();
+        if let Some(mode) = self.mode {
+            options = options.with_mode(mode);
+        }
+        if let Some(template) = &self.template {
+            options = options.with_template(template.absolutize()?);
+        }
+        if let Some(init) = &self.init {
+            options = options.with_init(init);
+        }
+        Ok(options)
+    }
+}
+
+fn parse_mode(mode: &str) -> Result<u32, String> {
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    u32::from_str_radix(digits, 8).map_err(|_| format!("Invalid octal mode: {}", mode))
+}
+
+pub fn run(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &EnsureCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let options = command.options()?;
+    for source in opts.sources()? {
+        let path = corpus.ensure(source.as_path(), &options)?;
+        printer.entry(&corpus, &path, &source)?;
+    }
+    Ok(0)
+}
@@ -0,0 +1,312 @@
+use crate::options::CorpusOptions;
+use crate::output::{self, Printer};
+use clap::Clap;
+use corpus::{Corpus, Entry};
+use path_absolutize::Absolutize;
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Which entries a command operates on.
+#[derive(Clap, Debug)]
+pub struct Scope {
+    /// Uses every entry in the corpus, rather than only those at or below
+    /// the source path.
+    #[clap(long)]
+    pub all: bool,
+}
+
+impl Scope {
+    fn entries<'a>(
+        &self,
+        corpus: &'a Corpus,
+        opts: &CorpusOptions,
+    ) -> anyhow::Result<Box<dyn Iterator<Item = Entry> + 'a>> {
+        if self.all {
+            return Ok(Box::new(corpus.entries()));
+        }
+
+        let source = opts.source_or_cwd()?;
+        let path = corpus.path(source.as_path());
+        let own = if path.exists() {
+            Some(Entry {
+                path,
+                source_path: source.clone(),
+                depth: 0,
+            })
+        } else {
+            None
+        };
+        Ok(Box::new(
+            own.into_iter().chain(corpus.descendants(source.as_path())),
+        ))
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct LsCommand {
+    #[clap(flatten)]
+    pub scope: Scope,
+
+    /// Prints the source paths of the entries, rather than the entries.
+    #[clap(long)]
+    pub sources: bool,
+}
+
+#[derive(Clap, Debug)]
+pub struct TreeCommand {
+    #[clap(flatten)]
+    pub scope: Scope,
+
+    /// Only renders the orphaned entries (whose source paths no longer
+    /// exist), along with the entries leading to them.
+    #[clap(long)]
+    pub orphaned: bool,
+
+    /// Only renders entries this many levels below the top of the tree.
+    #[clap(long)]
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Clap, Debug)]
+pub struct PruneCommand {
+    #[clap(flatten)]
+    pub scope: Scope,
+
+    /// Only prints the entries which would be removed.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Clap, Debug)]
+pub struct MvCommand {
+    /// The source path whose entry is moved.
+    #[clap(parse(from_os_str))]
+    pub from: PathBuf,
+
+    /// The source path to move the entry to.
+    #[clap(parse(from_os_str))]
+    pub to: PathBuf,
+}
+
+pub fn run_ls(opts: &CorpusOptions, printer: &Printer, command: &LsCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    for entry in command.scope.entries(&corpus, opts)? {
+        if command.sources {
+            printer.source(&corpus, &entry.path, &entry.source_path)?;
+        } else {
+            printer.entry(&corpus, &entry.path, &entry.source_path)?;
+        }
+    }
+    Ok(0)
+}
+
+pub fn run_tree(opts: &CorpusOptions, command: &TreeCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let entries: Vec<Entry> = command
+        .scope
+        .entries(&corpus, opts)?
+        .filter(|entry| !matches!(command.max_depth, Some(max_depth) if entry.depth > max_depth))
+        .collect();
+    let sizes = disk_usages(&entries);
+
+    // With `--orphaned`, the lines for the entries leading to the current
+    // one are held back until an orphaned entry below them turns up.
+    let mut stdout = std::io::stdout();
+    let mut pending: Vec<(usize, String)> = Vec::new();
+    for entry in &entries {
+        let label = match entry.source_path.file_name() {
+            Some(name) if entry.depth > 0 => Path::new(name),
+            _ => entry.source_path.as_path(),
+        };
+        let is_orphaned = !entry.source_path.exists();
+        let line = format!(
+            "{}{}{}",
+            "  ".repeat(entry.depth),
+            label.to_string_lossy(),
+            annotation(&entry.path, sizes.get(&entry.path).copied(), is_orphaned)
+        );
+
+        if !command.orphaned {
+            writeln!(stdout, "{}", line)?;
+            continue;
+        }
+        pending.retain(|(depth, _)| *depth < entry.depth);
+        if is_orphaned {
+            for (_, line) in pending.drain(..) {
+                writeln!(stdout, "{}", line)?;
+            }
+            writeln!(stdout, "{}", line)?;
+        } else {
+            pending.push((entry.depth, line));
+        }
+    }
+    Ok(0)
+}
+
+/// The annotation following an entry in `corpus tree`, e.g.
+/// ` [dir, 12 KiB, 2021-06-01 12:00, orphaned]`.
+fn annotation(entry: &Path, size: Option<u64>, is_orphaned: bool) -> String {
+    let mut parts = Vec::new();
+    if let Ok(metadata) = std::fs::symlink_metadata(entry) {
+        parts.push(output::kind(&metadata).to_string());
+        if let Some(size) = size {
+            parts.push(human_size(size));
+        }
+        if let Ok(modified) = metadata.modified() {
+            parts.push(timestamp(modified));
+        }
+    }
+    if is_orphaned {
+        parts.push("orphaned".to_string());
+    }
+    format!(" [{}]", parts.join(", "))
+}
+
+/// The total size of the files at or below each of the `entries`, without
+/// following symlinks.
+///
+/// Nested entries are sized while walking the entries enclosing them, so
+/// that each file is only visited once.
+fn disk_usages(entries: &[Entry]) -> HashMap<PathBuf, u64> {
+    let paths: HashSet<&Path> = entries.iter().map(|entry| entry.path.as_path()).collect();
+    let mut sizes = HashMap::new();
+    for entry in entries {
+        if sizes.contains_key(&entry.path) {
+            continue;
+        }
+        if let Ok(metadata) = std::fs::symlink_metadata(&entry.path) {
+            disk_usage(&entry.path, &metadata, &paths, &mut sizes);
+        }
+    }
+    sizes
+}
+
+/// The total size of the files at or below `path`, recording it in `sizes`
+/// for `path` and any of the `paths` below it.
+fn disk_usage(
+    path: &Path,
+    metadata: &Metadata,
+    paths: &HashSet<&Path>,
+    sizes: &mut HashMap<PathBuf, u64>,
+) -> u64 {
+    let size = if metadata.is_dir() {
+        std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|child| {
+                let metadata = child.metadata().ok()?;
+                Some(disk_usage(&child.path(), &metadata, paths, sizes))
+            })
+            .sum()
+    } else {
+        metadata.len()
+    };
+    if paths.contains(path) {
+        sizes.insert(path.to_path_buf(), size);
+    }
+    size
+}
+
+/// Formats a size in bytes with binary units, e.g. `1.5 KiB`.
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size < 10.0 {
+        format!("{:.1} {}", size, UNITS[unit])
+    } else {
+        format!("{:.0} {}", size, UNITS[unit])
+    }
+}
+
+/// Formats a time as a UTC `YYYY-MM-DD HH:MM` timestamp.
+fn timestamp(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let (days, seconds) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+
+    // Converts days since the epoch to a civil date (see Howard Hinnant's
+    // `civil_from_days`).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+pub fn run_prune(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &PruneCommand,
+) -> anyhow::Result<i32> {
+    let is_shared_root = opts
+        .kind
+        .as_deref()
+        .unwrap_or("xdg-data")
+        .starts_with("xdg-");
+    if opts.name.is_none() && is_shared_root {
+        anyhow::bail!(
+            "Refusing to prune a shared root without `--name`, since it holds other tools' data"
+        );
+    }
+
+    let corpus = opts.build(false)?;
+    let mut removed: Vec<PathBuf> = Vec::new();
+    for entry in command.scope.entries(&corpus, opts)? {
+        if entry.source_path.exists() || removed.iter().any(|dir| entry.path.starts_with(dir)) {
+            continue;
+        }
+
+        if !command.dry_run {
+            if entry.path.is_dir() {
+                std::fs::remove_dir_all(&entry.path)?;
+            } else {
+                std::fs::remove_file(&entry.path)?;
+            }
+            corpus.remove_locks(&entry.path)?;
+        }
+        printer.entry(&corpus, &entry.path, &entry.source_path)?;
+        removed.push(entry.path);
+    }
+
+    if !command.dry_run {
+        corpus.prune_links()?;
+    }
+    Ok(0)
+}
+
+pub fn run_mv(opts: &CorpusOptions, printer: &Printer, command: &MvCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let to_source = command.to.absolutize()?.to_path_buf();
+    let to = corpus.move_entry(command.from.absolutize()?.as_ref(), to_source.as_path())?;
+
+    printer.entry(&corpus, &to, &to_source)?;
+    Ok(0)
+}
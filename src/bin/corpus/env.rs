@@ -0,0 +1,243 @@
+use crate::options::{CorpusOptions, NearestOptions};
+use crate::output::{Format, Printer};
+use crate::profile::Config;
+use clap::Clap;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The syntax of the variable assignments printed by `env`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    /// `export` and `unset`, for bash, zsh and other POSIX shells.
+    Posix,
+
+    /// `set -gx` and `set -e`.
+    Fish,
+
+    /// `NAME='value'` lines, as read by dotenv libraries.
+    Dotenv,
+}
+
+impl FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bash" | "zsh" | "sh" => Self::Posix,
+            "fish" => Self::Fish,
+            "dotenv" => Self::Dotenv,
+            _ => return Err("Invalid option".to_string()),
+        })
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct EnvCommand {
+    /// The profiles to describe the entries of, in one go. Their variables
+    /// are prefixed with `CORPUS_<PROFILE>_` rather than `CORPUS_`.
+    pub profiles: Vec<String>,
+
+    /// The syntax to print: `bash` (the default), `zsh`, `fish` or `dotenv`.
+    /// `--format json` instead prints one object per line.
+    #[clap(long, default_value = "bash")]
+    pub shell: Dialect,
+
+    #[clap(flatten)]
+    pub nearest: NearestOptions,
+}
+
+/// The variables describing an entry.
+#[derive(Debug, Serialize)]
+struct Variables {
+    profile: Option<String>,
+    entry: String,
+    nearest: Option<String>,
+    nearest_source: Option<String>,
+    source: String,
+    exists: bool,
+}
+
+impl Variables {
+    fn new(
+        opts: &CorpusOptions,
+        nearest: &NearestOptions,
+        profile: Option<&str>,
+        source: &Path,
+    ) -> anyhow::Result<Self> {
+        let corpus = opts.build(false)?;
+        let entry = corpus.record_path(source)?;
+        let nearest = nearest.query(&corpus).find(source);
+
+        Ok(Self {
+            profile: profile.map(str::to_string),
+            exists: entry.exists(),
+            entry: entry.to_string_lossy().into_owned(),
+            nearest: nearest
+                .as_ref()
+                .map(|nearest| nearest.path.to_string_lossy().into_owned()),
+            nearest_source: nearest
+                .as_ref()
+                .map(|nearest| nearest.source_path.to_string_lossy().into_owned()),
+            source: source.to_string_lossy().into_owned(),
+        })
+    }
+
+    fn write(&self, out: &mut impl Write, dialect: Dialect) -> std::io::Result<()> {
+        let prefix = match &self.profile {
+            Some(profile) => format!("CORPUS_{}_", identifiers(profile).0),
+            None => "CORPUS_".to_string(),
+        };
+        let exists = if self.exists { "1" } else { "0" };
+        let variables = [
+            ("ENTRY", Some(self.entry.as_str())),
+            ("NEAREST", self.nearest.as_deref()),
+            ("NEAREST_SOURCE", self.nearest_source.as_deref()),
+            ("SOURCE", Some(self.source.as_str())),
+            ("EXISTS", Some(exists)),
+        ];
+
+        for (name, value) in variables.iter() {
+            let name = format!("{}{}", prefix, name);
+            write_variable(out, dialect, &name, *value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the assignment of a variable, or its removal if it has no `value`.
+pub fn write_variable(
+    out: &mut impl Write,
+    dialect: Dialect,
+    name: &str,
+    value: Option<&str>,
+) -> std::io::Result<()> {
+    match (dialect, value) {
+        (Dialect::Posix, Some(value)) => writeln!(out, "export {}={}", name, shell_quote(value)),
+        (Dialect::Posix, None) => writeln!(out, "unset {}", name),
+        (Dialect::Fish, Some(value)) => writeln!(out, "set -gx {} {}", name, fish_quote(value)),
+        (Dialect::Fish, None) => writeln!(out, "set -e {}", name),
+        (Dialect::Dotenv, Some(value)) => writeln!(out, "{}={}", name, dotenv_quote(value)),
+        (Dialect::Dotenv, None) => Ok(()),
+    }
+}
+
+pub fn run(opts: &CorpusOptions, printer: &Printer, command: &EnvCommand) -> anyhow::Result<i32> {
+    if let Format::Nul = printer.format {
+        anyhow::bail!("`env` can't print NUL-separated output, use `--format plain` or `json`");
+    }
+
+    // Profile names are squashed into identifiers, so distinct names may
+    // share the same variables.
+    let mut prefixes: HashMap<String, &str> = HashMap::new();
+    for name in &command.profiles {
+        let prefix = identifiers(name).0;
+        match prefixes.insert(prefix.clone(), name) {
+            Some(other) if other != name => anyhow::bail!(
+                "The profiles `{}` and `{}` would both set the `CORPUS_{}_*` variables",
+                other,
+                name,
+                prefix
+            ),
+            _ => {}
+        }
+    }
+
+    let source = opts.source_or_cwd()?;
+
+    let mut descriptions = Vec::new();
+    if command.profiles.is_empty() {
+        descriptions.push(Variables::new(opts, &command.nearest, None, &source)?);
+    } else {
+        if opts.profile.is_some() {
+            anyhow::bail!("`--profile` can't be combined with the profiles given to `env`");
+        }
+
+        let mut config = Config::load()?;
+        for name in &command.profiles {
+            let profile = config.profile(name)?;
+            let mut opts = opts.clone();
+            opts.apply(&profile);
+            let mut nearest = command.nearest.clone();
+            nearest.apply(&profile);
+            descriptions.push(Variables::new(&opts, &nearest, Some(name), &source)?);
+        }
+    }
+
+    let mut stdout = std::io::stdout();
+    for variables in descriptions {
+        match printer.format {
+            Format::Json => writeln!(stdout, "{}", serde_json::to_string(&variables)?)?,
+            Format::Plain => variables.write(&mut stdout, command.shell)?,
+            Format::Nul => unreachable!("rejected above"),
+        }
+    }
+    Ok(0)
+}
+
+/// The upper and lower case forms of `name`, usable in shell identifiers.
+pub fn identifiers(name: &str) -> (String, String) {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    (name.to_ascii_uppercase(), name.to_ascii_lowercase())
+}
+
+/// Quotes `value` for POSIX shells.
+pub fn shell_quote(value: &str) -> String {
+    String::from_utf8_lossy(&shell_quote_bytes(value.as_bytes())).into_owned()
+}
+
+/// Quotes `value` for fish.
+pub fn fish_quote(value: &str) -> String {
+    String::from_utf8_lossy(&fish_quote_bytes(value.as_bytes())).into_owned()
+}
+
+/// Quotes the raw bytes of `value` for POSIX shells, which (unlike a `str`)
+/// may be a path which isn't valid UTF-8.
+pub fn shell_quote_bytes(value: &[u8]) -> Vec<u8> {
+    quote_bytes(value, |byte| match byte {
+        b'\'' => Some(br"'\''"),
+        _ => None,
+    })
+}
+
+/// Quotes the raw bytes of `value` for fish.
+pub fn fish_quote_bytes(value: &[u8]) -> Vec<u8> {
+    quote_bytes(value, |byte| match byte {
+        b'\\' => Some(br"\\"),
+        b'\'' => Some(br"\'"),
+        _ => None,
+    })
+}
+
+/// Wraps `value` in single quotes, replacing the bytes `escape` returns an
+/// escape sequence for.
+fn quote_bytes(value: &[u8], escape: fn(u8) -> Option<&'static [u8]>) -> Vec<u8> {
+    let mut quoted = vec![b'\''];
+    for byte in value {
+        match escape(*byte) {
+            Some(escaped) => quoted.extend_from_slice(escaped),
+            None => quoted.push(*byte),
+        }
+    }
+    quoted.push(b'\'');
+    quoted
+}
+
+/// Quotes `value` for dotenv files, which only support escapes within
+/// double quotes.
+fn dotenv_quote(value: &str) -> String {
+    if value.contains(['\'', '\n']) {
+        let escaped = value
+            .replace('\\', r"\\")
+            .replace('"', "\\\"")
+            .replace('\n', r"\n");
+        format!("\"{}\"", escaped)
+    } else {
+        format!("'{}'", value)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::options::{CorpusOptions, NearestOptions};
+use clap::Clap;
+use corpus::{EnsureOptions, LockMode};
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Clap, Debug)]
+pub struct ExecCommand {
+    /// Uses the nearest existing entry for the source path or its parents.
+    ///
+    /// Exits with 1 if there is none (unless `--ensure` is given, in which
+    /// case the entry for the source path is created instead).
+    #[clap(long)]
+    pub nearest: bool,
+
+    #[clap(flatten)]
+    pub nearest_options: NearestOptions,
+
+    /// Creates the entry (as a directory) unless it already exists.
+    #[clap(long)]
+    pub ensure: bool,
+
+    /// Runs the command in the entry, rather than the current directory.
+    #[clap(long)]
+    pub cd: bool,
+
+    /// The command to run, and its arguments, in which `{entry}` and
+    /// `{source}` are replaced by the entry and source paths.
+    #[clap(required = true, last = true)]
+    pub command: Vec<String>,
+}
+
+#[derive(Clap, Debug)]
+pub struct LockCommand {
+    /// Takes a shared rather than an exclusive lock.
+    #[clap(long)]
+    pub shared: bool,
+
+    /// Fails immediately, rather than waiting, if the entry is locked.
+    #[clap(long)]
+    pub no_wait: bool,
+
+    /// The command to run, and its arguments.
+    #[clap(required = true, last = true)]
+    pub command: Vec<String>,
+}
+
+pub fn run_exec(opts: &CorpusOptions, command: &ExecCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+
+    let nearest = if command.nearest {
+        command
+            .nearest_options
+            .query(&corpus)
+            .find(source.as_path())
+            .map(|entry| (entry.path, entry.source_path))
+    } else {
+        None
+    };
+    let (entry, source) = match nearest {
+        Some(nearest) => nearest,
+        None if command.nearest && !command.ensure => {
+            eprintln!("No entry for {}", source.display());
+            return Ok(1);
+        }
+        None if command.ensure => (
+            corpus.ensure(source.as_path(), &EnsureOptions::new())?,
+            source,
+        ),
+        None => (corpus.record_path(source.as_path())?, source),
+    };
+
+    let mut args = command
+        .command
+        .iter()
+        .map(|arg| substitute(arg, &entry, &source));
+    let mut child = Command::new(args.next().unwrap());
+    child
+        .args(args)
+        .env("CORPUS_ENTRY", &entry)
+        .env("CORPUS_SOURCE", &source);
+    if command.cd {
+        child.current_dir(&entry);
+    }
+
+    let status = child.status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Replaces the `{entry}` and `{source}` placeholders in `arg`.
+///
+/// An argument consisting of just a placeholder is replaced by the path
+/// as-is, so that it needn't be valid UTF-8.
+fn substitute(arg: &str, entry: &Path, source: &Path) -> OsString {
+    match arg {
+        "{entry}" => entry.into(),
+        "{source}" => source.into(),
+        _ => arg
+            .replace("{entry}", &entry.to_string_lossy())
+            .replace("{source}", &source.to_string_lossy())
+            .into(),
+    }
+}
+
+pub fn run_lock(opts: &CorpusOptions, command: &LockCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let path = opts.source()?;
+
+    let mode = if command.shared {
+        LockMode::Shared
+    } else {
+        LockMode::Exclusive
+    };
+    let _lock = if command.no_wait {
+        match corpus.try_lock(path.as_deref(), mode)? {
+            Some(lock) => lock,
+            None => anyhow::bail!("The entry is locked by another process"),
+        }
+    } else {
+        corpus.lock(path.as_deref(), mode)?
+    };
+
+    let (program, args) = command.command.split_first().unwrap();
+    let status = Command::new(program).args(args).status()?;
+    Ok(status.code().unwrap_or(1))
+}
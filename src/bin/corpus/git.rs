@@ -0,0 +1,177 @@
+use crate::env::{write_variable, Dialect};
+use crate::options::CorpusOptions;
+use crate::output::{Format, Printer};
+use clap::Clap;
+use corpus::{EnsureOptions, GitRepo};
+use path_absolutize::Absolutize;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[derive(Clap, Debug)]
+pub enum GitCommand {
+    /// Creates a repository for the source directory, with its git dir at
+    /// the entry rather than in a `.git` directory.
+    Init(InitCommand),
+
+    /// Clones a repository, with its git dir at the entry for the directory
+    /// it is cloned into.
+    Clone(CloneCommand),
+
+    /// Prints `GIT_DIR` and `GIT_WORK_TREE` for the nearest repository.
+    ///
+    /// Exits with 1 (after printing `unset`s) if there is none.
+    StatusEnv(StatusEnvCommand),
+}
+
+#[derive(Clap, Debug)]
+pub struct InitCommand {
+    /// Further arguments for `git init`.
+    #[clap(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clap, Debug)]
+pub struct CloneCommand {
+    /// The repository to clone.
+    pub repository: String,
+
+    /// The directory to clone into (defaults to `--path`, or else a
+    /// directory named after the repository, as with `git clone`).
+    #[clap(parse(from_os_str))]
+    pub directory: Option<PathBuf>,
+
+    /// Further arguments for `git clone`.
+    #[clap(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Clap, Debug)]
+pub struct StatusEnvCommand {
+    /// The syntax to print: `bash` (the default), `zsh`, `fish` or `dotenv`.
+    /// `--format json` instead prints an object.
+    #[clap(long, default_value = "bash")]
+    pub shell: Dialect,
+}
+
+/// The JSON description of a repository.
+#[derive(Debug, Serialize)]
+struct Record {
+    git_dir: String,
+    work_tree: String,
+}
+
+pub fn run(opts: &CorpusOptions, printer: &Printer, command: &GitCommand) -> anyhow::Result<i32> {
+    match command {
+        GitCommand::Init(command) => run_init(opts, command),
+        GitCommand::Clone(command) => run_clone(opts, command),
+        GitCommand::StatusEnv(command) => run_status_env(opts, printer, command),
+    }
+}
+
+fn run_init(opts: &CorpusOptions, command: &InitCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+    let repo = corpus.git_repo(source.as_path());
+    corpus.ensure(source.as_path(), &EnsureOptions::new())?;
+
+    // With the work tree given, `git init` records it as `core.worktree`, so
+    // that `GIT_DIR` alone is enough to find it again.
+    let status = git(&repo).arg("init").args(&command.args).status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn run_clone(opts: &CorpusOptions, command: &CloneCommand) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let directory = match (&command.directory, opts.source()?) {
+        (Some(directory), _) => directory.absolutize()?.to_path_buf(),
+        (None, Some(source)) => source,
+        (None, None) => PathBuf::from(humanish(&command.repository))
+            .absolutize()?
+            .to_path_buf(),
+    };
+    let repo = corpus.git_repo(directory.as_path());
+    if repo.git_dir.exists() {
+        anyhow::bail!("The git dir {} already exists", repo.git_dir.display());
+    }
+    corpus.prepare_path(directory.as_path())?;
+
+    let status = Command::new("git")
+        .arg("clone")
+        .arg("--separate-git-dir")
+        .arg(&repo.git_dir)
+        .args(&command.args)
+        .arg(&command.repository)
+        .arg(&repo.work_tree)
+        .status()?;
+    if !status.success() {
+        return Ok(status.code().unwrap_or(1));
+    }
+
+    // `--separate-git-dir` leaves a `.git` file pointing at the git dir,
+    // which is replaced by pointing the git dir back at the work tree.
+    std::fs::remove_file(repo.work_tree.join(".git"))?;
+    let status = git(&repo)
+        .args(["config", "core.worktree"])
+        .arg(&repo.work_tree)
+        .status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn run_status_env(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &StatusEnvCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let repo = corpus.find_git_repo(opts.source()?.as_deref());
+
+    let mut stdout = std::io::stdout();
+    match (printer.format, &repo) {
+        (Format::Json, Some(repo)) => {
+            let record = Record {
+                git_dir: repo.git_dir.to_string_lossy().into_owned(),
+                work_tree: repo.work_tree.to_string_lossy().into_owned(),
+            };
+            writeln!(stdout, "{}", serde_json::to_string(&record)?)?;
+        }
+        (Format::Json, None) => printer.missing()?,
+        (Format::Plain | Format::Nul, Some(repo)) => {
+            for (name, value) in repo.env().iter() {
+                write_variable(
+                    &mut stdout,
+                    command.shell,
+                    name,
+                    Some(&value.to_string_lossy()),
+                )?;
+            }
+        }
+        (Format::Plain | Format::Nul, None) => {
+            for name in ["GIT_DIR", "GIT_WORK_TREE"].iter() {
+                write_variable(&mut stdout, command.shell, name, None)?;
+            }
+        }
+    }
+    Ok(if repo.is_some() { 0 } else { 1 })
+}
+
+/// A `git` command pointed at `repo`.
+fn git(repo: &GitRepo) -> Command {
+    let mut command = Command::new("git");
+    command
+        .arg("--git-dir")
+        .arg(&repo.git_dir)
+        .arg("--work-tree")
+        .arg(&repo.work_tree);
+    command
+}
+
+/// The directory name `git clone` would pick for `repository`: its last
+/// component, without any `.git` suffix.
+fn humanish(repository: &str) -> &str {
+    let repository = repository.trim_end_matches('/');
+    let repository = repository.strip_suffix("/.git").unwrap_or(repository);
+    let name = repository.rsplit(['/', ':']).next().unwrap_or(repository);
+    name.strip_suffix(".git").unwrap_or(name)
+}
@@ -0,0 +1,146 @@
+use crate::env::{fish_quote_bytes, identifiers, shell_quote_bytes};
+use crate::options::CorpusOptions;
+use clap::Clap;
+use corpus::path_to_bytes;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The shells which `corpus init` can generate a script for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bash" => Self::Bash,
+            "zsh" => Self::Zsh,
+            "fish" => Self::Fish,
+            _ => return Err("Invalid option".to_string()),
+        })
+    }
+}
+
+#[derive(Clap, Debug)]
+pub struct InitCommand {
+    /// The shell to generate the script for: `bash`, `zsh` or `fish`.
+    pub shell: Shell,
+
+    /// The name of the function which jumps between a source directory and
+    /// its entry.
+    #[clap(long, default_value = "corpus-cd")]
+    pub cd: String,
+}
+
+/// The hook and `cd` function, shared by bash and zsh.
+const POSIX_FUNCTIONS: &str = r#"__HOOK__() {
+    if [ "${__PWD__-}" = "$PWD" ]; then
+        return
+    fi
+    __PWD__=$PWD
+
+    local dir
+    if dir=$(__CORPUS__ nearest 2>/dev/null); then
+        export __DIR__="$dir"
+        export __SOURCE__="$(__CORPUS__ source "$dir" 2>/dev/null)"
+    else
+        unset __DIR__ __SOURCE__
+    fi
+}
+
+__CD__() {
+    local target
+    if target=$(__CORPUS__ source "$PWD" 2>/dev/null); then
+        :
+    elif target=$(__CORPUS__ nearest 2>/dev/null); then
+        :
+    else
+        echo "__CD__: no entry for $PWD" >&2
+        return 1
+    fi
+    cd -- "$target" && __HOOK__
+}
+"#;
+
+const BASH_HOOK: &str = r#"
+if [[ ";${PROMPT_COMMAND:-};" != *";__HOOK__;"* ]]; then
+    PROMPT_COMMAND="__HOOK__${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+__HOOK__
+"#;
+
+const ZSH_HOOK: &str = r#"
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd __HOOK__
+__HOOK__
+"#;
+
+const FISH: &str = r#"function __HOOK__ --on-variable PWD
+    set -l dir (__CORPUS__ nearest 2>/dev/null)
+    if test $status -eq 0
+        set -gx __DIR__ $dir
+        set -gx __SOURCE__ (__CORPUS__ source $dir 2>/dev/null)
+    else
+        set -e __DIR__
+        set -e __SOURCE__
+    end
+end
+
+function __CD__
+    set -l target (__CORPUS__ source $PWD 2>/dev/null)
+    or set target (__CORPUS__ nearest 2>/dev/null)
+    or begin
+        echo "__CD__: no entry for $PWD" >&2
+        return 1
+    end
+    cd $target
+end
+
+__HOOK__
+"#;
+
+/// Prints a script which keeps `CORPUS_<NAME>_DIR` and `CORPUS_<NAME>_SOURCE`
+/// pointed at the nearest entry as the current directory changes.
+pub fn run(opts: &CorpusOptions, command: &InitCommand) -> anyhow::Result<i32> {
+    let name = opts
+        .profile
+        .as_deref()
+        .or(opts.name.as_deref())
+        .ok_or_else(|| anyhow::anyhow!("`corpus init` needs a `--profile` or `--name`"))?;
+    let (upper, lower) = identifiers(name);
+
+    let quote = match command.shell {
+        Shell::Bash | Shell::Zsh => shell_quote_bytes,
+        Shell::Fish => fish_quote_bytes,
+    };
+    // The arguments are quoted as raw bytes, so that paths which aren't valid
+    // UTF-8 survive into the script.
+    let exe = std::env::current_exe()?.into_os_string();
+    let corpus = std::iter::once(exe)
+        .chain(opts.args())
+        .map(|arg| quote(&path_to_bytes(Path::new(&arg))))
+        .collect::<Vec<_>>()
+        .join(&b' ');
+
+    let template = match command.shell {
+        Shell::Bash => format!("{}{}", POSIX_FUNCTIONS, BASH_HOOK),
+        Shell::Zsh => format!("{}{}", POSIX_FUNCTIONS, ZSH_HOOK),
+        Shell::Fish => FISH.to_string(),
+    };
+    let script = template
+        .replace("__HOOK__", &format!("__corpus_{}_hook", lower))
+        .replace("__PWD__", &format!("__corpus_{}_pwd", lower))
+        .replace("__DIR__", &format!("CORPUS_{}_DIR", upper))
+        .replace("__SOURCE__", &format!("CORPUS_{}_SOURCE", upper))
+        .replace("__CD__", &command.cd);
+
+    let script: Vec<&[u8]> = script.split("__CORPUS__").map(str::as_bytes).collect();
+    std::io::stdout().write_all(&script.join(corpus.as_slice()))?;
+    Ok(0)
+}
@@ -0,0 +1,70 @@
+use crate::options::CorpusOptions;
+use crate::output::Printer;
+use clap::Clap;
+use std::path::PathBuf;
+
+#[derive(Clap, Debug)]
+pub struct LinkCommand {
+    /// The name of the symlink within the source directory (e.g. `.venv`).
+    #[clap(name = "as", long = "as", value_name = "NAME", parse(from_os_str))]
+    pub name: PathBuf,
+}
+
+#[derive(Clap, Debug)]
+pub struct UnlinkCommand {
+    /// Only removes the symlink with this name, rather than every link in
+    /// the source directory.
+    #[clap(name = "as", long = "as", value_name = "NAME", parse(from_os_str))]
+    pub name: Option<PathBuf>,
+
+    /// Removes every link created by `corpus link`, wherever it is.
+    #[clap(long, conflicts_with = "as")]
+    pub all: bool,
+}
+
+pub fn run_link(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &LinkCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+
+    match corpus.link(source.as_path(), &command.name)? {
+        Some(link) => {
+            printer.source(&corpus, &link.entry, &link.path)?;
+            Ok(0)
+        }
+        None => {
+            eprintln!(
+                "Skipping {}, which exists and isn't a link to the entry",
+                source.join(&command.name).display()
+            );
+            Ok(1)
+        }
+    }
+}
+
+pub fn run_unlink(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &UnlinkCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let source = opts.source_or_cwd()?;
+
+    let mut code = 1;
+    for link in corpus.links()? {
+        let selected = command.all
+            || (link.path.parent() == Some(source.as_path())
+                && command
+                    .name
+                    .as_ref()
+                    .is_none_or(|name| link.path == source.join(name)));
+        if selected && corpus.unlink(&link)? {
+            printer.source(&corpus, &link.entry, &link.path)?;
+            code = 0;
+        }
+    }
+    Ok(code)
+}
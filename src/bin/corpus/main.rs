@@ -0,0 +1,193 @@
+mod adopt;
+mod ensure;
+mod entries;
+mod env;
+mod exec;
+mod git;
+mod init;
+mod link;
+mod options;
+mod output;
+mod path;
+mod profile;
+
+use clap::Clap;
+
+use crate::options::CorpusOptions;
+use crate::output::{Format, Printer};
+use crate::path::LegacyOptions;
+use crate::profile::{Config, Profile};
+
+/// Each subcommand exits with 0 on success, 1 when there is nothing to
+/// report (e.g. no nearest entry, or a path which is not an entry), and 2 on
+/// errors. `exec` and `lock` instead exit with the code of their command.
+#[derive(Clap, Debug)]
+#[clap(version, author)]
+pub struct Options {
+    #[clap(flatten)]
+    pub corpus: CorpusOptions,
+
+    /// The output format: `plain` (the default) prints one path per line,
+    /// `nul` prints NUL-terminated paths, and `json` prints one object per
+    /// line with the `corpus_path`, `source_path`, `root`, `relative_to`,
+    /// `exists` and `kind` of each entry.
+    #[clap(long, global = true, default_value = "plain")]
+    pub format: Format,
+
+    #[clap(flatten)]
+    pub legacy: LegacyOptions,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Clap, Debug)]
+pub enum Command {
+    /// Prints the entry for the source path.
+    Path,
+
+    /// Prints the nearest existing entry for the source path or its parents.
+    ///
+    /// Exits with 1 if there is none.
+    Nearest(path::NearestCommand),
+
+    /// Prints the source path of an entry.
+    ///
+    /// Exits with 1 if the path is not an entry.
+    Source(path::SourceCommand),
+
+    /// Creates the entry for the source path, unless it already exists.
+    Ensure(ensure::EnsureCommand),
+
+    /// Lists the existing entries at or below the source path.
+    Ls(entries::LsCommand),
+
+    /// Renders the existing entries at or below the source path as a tree,
+    /// annotated with their kind, size and mtime, and whether their source
+    /// paths still exist.
+    Tree(entries::TreeCommand),
+
+    /// Removes the entries whose source paths no longer exist, along with
+    /// their lock files (see `lock`), and forgets the links (see `link`)
+    /// which were removed along with them.
+    Prune(entries::PruneCommand),
+
+    /// Moves the entry for one source path to another, e.g. after the source
+    /// directory itself was moved. The source directories are left alone,
+    /// but the links (see `link`) to the entry follow it.
+    Mv(entries::MvCommand),
+
+    /// Moves an in-tree file or directory (e.g. `.venv`) to the entry for the
+    /// source directory, refusing to overwrite an existing entry.
+    Adopt(adopt::AdoptCommand),
+
+    /// Moves the entry for the source directory back into it, reversing
+    /// `adopt`.
+    Unadopt(adopt::UnadoptCommand),
+
+    /// Creates a symlink in the source directory pointing at the entry, for
+    /// tools which can't be configured to use the corpus.
+    ///
+    /// Links are recorded in the root, so that `unlink` and `prune` can clean
+    /// them up. Exits with 1, without linking, if a real file is in the way.
+    Link(link::LinkCommand),
+
+    /// Removes the links created by `link` in the source directory.
+    ///
+    /// Exits with 1 if there were none.
+    Unlink(link::UnlinkCommand),
+
+    /// Runs a command with `CORPUS_ENTRY` and `CORPUS_SOURCE` set (and
+    /// `{entry}` and `{source}` replaced in its arguments).
+    Exec(exec::ExecCommand),
+
+    /// Prints `export` statements describing the entry, the nearest entry
+    /// and the source path, for one or more profiles.
+    Env(env::EnvCommand),
+
+    /// Runs a command while holding a lock on the entry.
+    Lock(exec::LockCommand),
+
+    /// Prints a shell script which exports `CORPUS_<NAME>_DIR` and
+    /// `CORPUS_<NAME>_SOURCE` for the nearest entry whenever the current
+    /// directory changes, and defines a `corpus-cd` function which jumps
+    /// between a source directory and its entry.
+    ///
+    /// `<NAME>` is that of the `--profile` (or else the `--name`), e.g.
+    /// `eval "$(corpus init bash --profile venv)"`.
+    Init(init::InitCommand),
+
+    /// Manages git repositories whose git dirs are kept in the corpus.
+    #[clap(subcommand)]
+    Git(git::GitCommand),
+
+    /// Inspects the profiles defined in the config file.
+    #[clap(subcommand)]
+    Profiles(profile::ProfilesCommand),
+}
+
+fn main() {
+    let code = run(Options::parse()).unwrap_or_else(|error| {
+        eprintln!("Error: {:?}", error);
+        2
+    });
+    std::process::exit(code);
+}
+
+fn run(mut opts: Options) -> anyhow::Result<i32> {
+    if let Some(Command::Profiles(command)) = &opts.command {
+        return profile::run(command);
+    }
+
+    let profile = match &opts.corpus.profile {
+        Some(name) => Config::load()?.profile(name)?,
+        None => Profile::default(),
+    };
+    opts.corpus.apply(&profile);
+
+    let batch = matches!(
+        opts.command,
+        Some(Command::Path)
+            | Some(Command::Nearest(_))
+            | Some(Command::Source(_))
+            | Some(Command::Ensure(_))
+    );
+    if opts.corpus.is_batch() && !batch {
+        anyhow::bail!(
+            "several paths (and `--stdin`) are only supported by `path`, `nearest`, `source` and `ensure`"
+        );
+    }
+
+    let corpus = &opts.corpus;
+    let printer = &Printer {
+        format: opts.format,
+    };
+
+    match &mut opts.command {
+        None => path::run_legacy(corpus, printer, &mut opts.legacy, &profile),
+        Some(Command::Path) => path::run_path(corpus, printer),
+        Some(Command::Nearest(command)) => {
+            command.options.apply(&profile);
+            path::run_nearest(corpus, printer, command)
+        }
+        Some(Command::Source(command)) => path::run_source(corpus, printer, command),
+        Some(Command::Ensure(command)) => ensure::run(corpus, printer, command),
+        Some(Command::Ls(command)) => entries::run_ls(corpus, printer, command),
+        Some(Command::Tree(command)) => entries::run_tree(corpus, command),
+        Some(Command::Prune(command)) => entries::run_prune(corpus, printer, command),
+        Some(Command::Mv(command)) => entries::run_mv(corpus, printer, command),
+        Some(Command::Adopt(command)) => adopt::run_adopt(corpus, printer, command),
+        Some(Command::Unadopt(command)) => adopt::run_unadopt(corpus, printer, command),
+        Some(Command::Link(command)) => link::run_link(corpus, printer, command),
+        Some(Command::Unlink(command)) => link::run_unlink(corpus, printer, command),
+        Some(Command::Exec(command)) => {
+            command.nearest_options.apply(&profile);
+            exec::run_exec(corpus, command)
+        }
+        Some(Command::Env(command)) => env::run(corpus, printer, command),
+        Some(Command::Lock(command)) => exec::run_lock(corpus, command),
+        Some(Command::Init(command)) => init::run(corpus, command),
+        Some(Command::Git(command)) => git::run(corpus, printer, command),
+        Some(Command::Profiles(_)) => unreachable!("Profiles are handled above."),
+    }
+}
@@ -0,0 +1,298 @@
+use crate::profile::Profile;
+use clap::Clap;
+use corpus::{builder, path_from_bytes, Corpus, CorpusError, NearestQuery, RootLocation};
+use path_absolutize::Absolutize;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// The directories which can be used as the "relative" directory.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelativeToKind {
+    /// The home directory (the default).
+    Home,
+
+    /// The filesystem root, so that whole absolute paths are mirrored.
+    Root,
+
+    /// The root of the enclosing git repository.
+    GitRoot,
+}
+
+impl FromStr for RelativeToKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "home" => Self::Home,
+            "root" => Self::Root,
+            "git-root" => Self::GitRoot,
+            _ => return Err("Invalid option".to_string()),
+        })
+    }
+}
+
+/// The options describing the corpus, shared by every subcommand.
+#[derive(Clap, Debug, Clone)]
+pub struct CorpusOptions {
+    /// The source path (defaults to the current directory).
+    ///
+    /// `path`, `nearest`, `source` and `ensure` accept several paths, and
+    /// print one result per path, in order.
+    #[clap(
+        short,
+        long,
+        global = true,
+        multiple_occurrences = true,
+        multiple_values = false,
+        parse(from_os_str)
+    )]
+    pub path: Vec<PathBuf>,
+
+    /// Also reads source paths from stdin, one per line (or NUL-separated,
+    /// if the input contains a NUL byte).
+    #[clap(long, global = true)]
+    pub stdin: bool,
+
+    /// Uses the settings of a profile from the config file as defaults.
+    ///
+    /// Profiles are read from `$XDG_CONFIG_HOME/corpus/config.toml`, and any
+    /// flags given explicitly take precedence over them.
+    #[clap(long, global = true)]
+    pub profile: Option<String>,
+
+    /// The root location: `xdg-data` (the default), `xdg-config`,
+    /// `xdg-cache`, or a path.
+    #[clap(long, global = true)]
+    pub kind: Option<String>,
+
+    #[clap(short, long, global = true)]
+    pub ext: Option<String>,
+
+    #[clap(short, long, global = true)]
+    pub name: Option<String>,
+
+    /// Maps source paths relative to this directory, rather than $HOME.
+    #[clap(
+        long,
+        global = true,
+        conflicts_with = "relative-to-kind",
+        parse(from_os_str)
+    )]
+    pub relative_to: Option<PathBuf>,
+
+    /// Maps source paths relative to a well known directory: `home` (the
+    /// default), `root` or `git-root`.
+    ///
+    /// The git root is that of the repository enclosing the (first) source
+    /// path, or of the current directory when resolving source paths.
+    #[clap(long, global = true)]
+    pub relative_to_kind: Option<RelativeToKind>,
+}
+
+impl CorpusOptions {
+    /// Fills in any options which weren't given explicitly from `profile`.
+    pub fn apply(&mut self, profile: &Profile) {
+        self.name = self.name.take().or_else(|| profile.name.clone());
+        self.kind = self.kind.take().or_else(|| profile.kind.clone());
+        self.ext = self.ext.take().or_else(|| profile.ext.clone());
+        if self.relative_to.is_none() && self.relative_to_kind.is_none() {
+            self.relative_to = profile.relative_to.clone();
+            self.relative_to_kind = profile.relative_to_kind;
+        }
+    }
+
+    /// Whether several source paths may be given.
+    pub fn is_batch(&self) -> bool {
+        self.stdin || self.path.len() > 1
+    }
+
+    /// The absolute source path, if one was given.
+    ///
+    /// Only the first one is used, where several were given.
+    pub fn source(&self) -> anyhow::Result<Option<PathBuf>> {
+        Ok(match self.path.first() {
+            Some(path) => Some(path.absolutize()?.to_path_buf()),
+            None => None,
+        })
+    }
+
+    /// The absolute source paths given by `--path` and `--stdin`, in order,
+    /// defaulting to the current directory.
+    pub fn sources(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut paths = self.path.clone();
+        if self.stdin {
+            let mut input = Vec::new();
+            std::io::stdin().read_to_end(&mut input)?;
+            paths.extend(split_paths(&input));
+        } else if paths.is_empty() {
+            return Ok(vec![std::env::current_dir()?]);
+        }
+
+        let current_dir = std::env::current_dir()?;
+        Ok(paths
+            .into_iter()
+            .map(|path| current_dir.join(path).absolutize().map(|p| p.to_path_buf()))
+            .collect::<Result<_, _>>()?)
+    }
+
+    /// The absolute source path, defaulting to the current directory.
+    pub fn source_or_cwd(&self) -> anyhow::Result<PathBuf> {
+        match self.source()? {
+            Some(source) => Ok(source),
+            None => Ok(std::env::current_dir()?),
+        }
+    }
+
+    /// The options describing the corpus, as command line arguments, so that
+    /// generated scripts can call back into `corpus` with the same corpus.
+    ///
+    /// Relative paths are made absolute, as such scripts run elsewhere.
+    pub fn args(&self) -> Vec<OsString> {
+        let kind = self
+            .kind
+            .as_ref()
+            .map(|kind| match RootLocation::from(kind.as_str()) {
+                RootLocation::Raw(path) => absolute(&path),
+                _ => kind.into(),
+            });
+
+        let mut args = Vec::new();
+        let options = [
+            ("--profile", self.profile.as_ref().map(OsString::from)),
+            ("--kind", kind),
+            ("--ext", self.ext.as_ref().map(OsString::from)),
+            ("--name", self.name.as_ref().map(OsString::from)),
+        ];
+        for (flag, value) in options.iter() {
+            if let Some(value) = value {
+                args.push(flag.into());
+                args.push(value.clone());
+            }
+        }
+
+        if let Some(relative_to) = &self.relative_to {
+            args.push("--relative-to".into());
+            args.push(absolute(relative_to));
+        } else if let Some(kind) = self.relative_to_kind {
+            let kind = match kind {
+                RelativeToKind::Home => "home",
+                RelativeToKind::Root => "root",
+                RelativeToKind::GitRoot => "git-root",
+            };
+            args.push("--relative-to-kind".into());
+            args.push(kind.into());
+        }
+        args
+    }
+
+    /// Builds the corpus.
+    ///
+    /// `from_entry` indicates that the source path is itself an entry (as
+    /// when resolving source paths), so that it can't be used to find the
+    /// enclosing git repository.
+    pub fn build(&self, from_entry: bool) -> anyhow::Result<Corpus> {
+        let kind = self.kind.as_deref().unwrap_or("xdg-data");
+        let mut builder = builder().with_root(kind);
+
+        builder = match (&self.relative_to, self.relative_to_kind) {
+            (Some(relative_to), _) => builder.relative_to(relative_to.absolutize()?),
+            (None, Some(RelativeToKind::Root)) => builder.relative_to("/"),
+            (None, Some(RelativeToKind::GitRoot)) => {
+                // The git root is found from a single path, which other paths
+                // may well be outside of.
+                if self.is_batch() && !from_entry {
+                    anyhow::bail!("`--relative-to-kind git-root` takes a single source path");
+                }
+                let start = match self.source()? {
+                    Some(path) if !from_entry => path,
+                    _ => std::env::current_dir().map_err(|_| CorpusError::InvalidCurrentDir)?,
+                };
+                let git_root = start
+                    .ancestors()
+                    .find(|dir| dir.join(".git").exists())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("{} is not inside a git repository", start.display())
+                    })?;
+                builder.relative_to(git_root)
+            }
+            (None, Some(RelativeToKind::Home)) | (None, None) => builder.relative_to_home()?,
+        };
+
+        if let Some(name) = &self.name {
+            builder = builder.with_name(name);
+        }
+
+        if let Some(ext) = &self.ext {
+            builder = builder.with_extension(ext);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// The options limiting the search for the nearest entry.
+#[derive(Clap, Debug, Clone)]
+pub struct NearestOptions {
+    /// Only looks this many parent directories upwards.
+    #[clap(long)]
+    pub max_depth: Option<usize>,
+
+    /// Stops looking upwards at a source directory.
+    ///
+    /// A bare name (e.g. `.git`) stops at the first directory containing it,
+    /// while anything containing a path separator is a boundary directory.
+    #[clap(long, number_of_values = 1)]
+    pub stop_at: Vec<String>,
+}
+
+impl NearestOptions {
+    /// Fills in any options which weren't given explicitly from `profile`.
+    pub fn apply(&mut self, profile: &Profile) {
+        self.max_depth = self.max_depth.or(profile.max_depth);
+        if self.stop_at.is_empty() {
+            self.stop_at = profile.stop_at.clone();
+        }
+    }
+
+    pub fn query<'c>(&self, corpus: &'c Corpus) -> NearestQuery<'c> {
+        let mut query = corpus.nearest();
+        if let Some(max_depth) = self.max_depth {
+            query = query.max_depth(max_depth);
+        }
+        for stop in &self.stop_at {
+            query = if stop.contains(std::path::MAIN_SEPARATOR) {
+                query.stop_at(stop)
+            } else {
+                query.stop_at_marker(stop)
+            };
+        }
+        query
+    }
+}
+
+/// Splits the paths read from stdin, on NUL bytes if there are any, or else
+/// on (possibly CRLF) newlines. Empty paths are skipped.
+fn split_paths(input: &[u8]) -> Vec<PathBuf> {
+    if input.contains(&b'\0') {
+        return input
+            .split(|byte| *byte == b'\0')
+            .filter(|path| !path.is_empty())
+            .map(path_from_bytes)
+            .collect();
+    }
+    input
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|path| !path.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+fn absolute(path: &Path) -> OsString {
+    let path = path.absolutize().unwrap_or_else(|_| path.into());
+    path.into_owned().into_os_string()
+}
@@ -0,0 +1,143 @@
+use corpus::{path_to_bytes, Corpus};
+use serde::Serialize;
+use std::fs::Metadata;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How paths are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// One path per line, as raw OS bytes.
+    Plain,
+
+    /// One JSON object per line, describing the entry.
+    Json,
+
+    /// NUL-terminated paths, as raw OS bytes.
+    Nul,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "plain" => Self::Plain,
+            "json" => Self::Json,
+            "nul" => Self::Nul,
+            _ => return Err("Invalid option".to_string()),
+        })
+    }
+}
+
+/// The JSON description of an entry.
+///
+/// JSON strings can't hold arbitrary bytes, so non-UTF-8 paths are converted
+/// lossily; use the plain or NUL formats where that matters.
+#[derive(Debug, Serialize)]
+struct Record {
+    corpus_path: String,
+    source_path: String,
+    root: String,
+    relative_to: Option<String>,
+    exists: bool,
+    kind: Option<&'static str>,
+}
+
+impl Record {
+    fn new(corpus: &Corpus, entry: &Path, source: &Path) -> Self {
+        let kind = std::fs::symlink_metadata(entry)
+            .ok()
+            .map(|metadata| kind(&metadata));
+
+        Self {
+            corpus_path: entry.to_string_lossy().into_owned(),
+            source_path: source.to_string_lossy().into_owned(),
+            root: corpus.root_location.to_string_lossy().into_owned(),
+            relative_to: corpus
+                .relative_base(source)
+                .map(|base| base.to_string_lossy().into_owned()),
+            exists: entry.exists(),
+            kind,
+        }
+    }
+}
+
+/// The kind of an entry, from its metadata (without following symlinks).
+pub fn kind(metadata: &Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+/// Writes the results of a command in the chosen [`Format`].
+#[derive(Debug, Clone, Copy)]
+pub struct Printer {
+    pub format: Format,
+}
+
+impl Printer {
+    /// Prints an entry, given as both its `entry` and `source` paths.
+    pub fn entry(&self, corpus: &Corpus, entry: &Path, source: &Path) -> anyhow::Result<()> {
+        self.print(corpus, entry, entry, source)
+    }
+
+    /// Prints the source path of an entry, given as both its `entry` and
+    /// `source` paths.
+    pub fn source(&self, corpus: &Corpus, entry: &Path, source: &Path) -> anyhow::Result<()> {
+        self.print(corpus, source, entry, source)
+    }
+
+    /// Prints an empty result, for the deprecated flags which print an empty
+    /// line rather than exiting with 1.
+    pub fn empty(&self) -> anyhow::Result<()> {
+        match self.format {
+            Format::Plain => write_path(Path::new(""), b'\n')?,
+            Format::Json | Format::Nul => {}
+        }
+        Ok(())
+    }
+
+    /// Prints a placeholder for an input without a result, so that batch
+    /// results stay aligned with their inputs: an empty path, or `null`.
+    pub fn missing(&self) -> anyhow::Result<()> {
+        match self.format {
+            Format::Plain => write_path(Path::new(""), b'\n')?,
+            Format::Nul => write_path(Path::new(""), b'\0')?,
+            Format::Json => writeln!(std::io::stdout(), "null")?,
+        }
+        Ok(())
+    }
+
+    fn print(
+        &self,
+        corpus: &Corpus,
+        primary: &Path,
+        entry: &Path,
+        source: &Path,
+    ) -> anyhow::Result<()> {
+        match self.format {
+            Format::Plain => write_path(primary, b'\n')?,
+            Format::Nul => write_path(primary, b'\0')?,
+            Format::Json => {
+                let record = Record::new(corpus, entry, source);
+                writeln!(std::io::stdout(), "{}", serde_json::to_string(&record)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_path(path: &Path, terminator: u8) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(&path_to_bytes(path))?;
+    stdout.write_all(&[terminator])
+}
@@ -0,0 +1,157 @@
+use crate::ensure::CreateAs;
+use crate::options::{CorpusOptions, NearestOptions};
+use crate::output::Printer;
+use crate::profile::{Anchor, Profile};
+use clap::Clap;
+use path_absolutize::Absolutize;
+use std::path::PathBuf;
+
+#[derive(Clap, Debug)]
+pub struct NearestCommand {
+    #[clap(flatten)]
+    pub options: NearestOptions,
+}
+
+#[derive(Clap, Debug)]
+pub struct SourceCommand {
+    /// The entry (defaults to `--path`, or the current directory).
+    #[clap(parse(from_os_str))]
+    pub entry: Option<PathBuf>,
+}
+
+/// The pre-subcommand flags, which are still accepted without a subcommand.
+#[derive(Clap, Debug)]
+pub struct LegacyOptions {
+    /// Deprecated: use `corpus nearest`.
+    #[clap(long)]
+    pub nearest: bool,
+
+    #[clap(flatten)]
+    pub nearest_options: NearestOptions,
+
+    /// Deprecated: use `corpus ensure`.
+    #[clap(short, long)]
+    pub create: Option<CreateAs>,
+
+    /// Deprecated: use `corpus source`.
+    #[clap(long, short)]
+    pub source_path: bool,
+}
+
+pub fn run_path(opts: &CorpusOptions, printer: &Printer) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let sources = opts.sources()?;
+    for source in &sources {
+        // Shortened entries are recorded, so that whoever creates them can
+        // still resolve their sources later.
+        let entry = corpus.record_path(source.as_path())?;
+        printer.entry(&corpus, &entry, source)?;
+    }
+    Ok(0)
+}
+
+pub fn run_nearest(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &NearestCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(false)?;
+    let query = command.options.query(&corpus);
+
+    let mut code = 0;
+    for source in opts.sources()? {
+        match query.find(source.as_path()) {
+            Some(entry) => printer.entry(&corpus, &entry.path, &entry.source_path)?,
+            None if opts.is_batch() => {
+                printer.missing()?;
+                code = 1;
+            }
+            None => code = 1,
+        }
+    }
+    Ok(code)
+}
+
+pub fn run_source(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    command: &SourceCommand,
+) -> anyhow::Result<i32> {
+    let corpus = opts.build(true)?;
+    let entries = match &command.entry {
+        Some(entry) => vec![entry.absolutize()?.to_path_buf()],
+        None => opts.sources()?,
+    };
+
+    let mut code = 0;
+    for entry in entries {
+        let source = Some(entry.as_path())
+            .filter(|entry| corpus.is_ancestor(*entry))
+            .and_then(|entry| corpus.get_source_path(entry));
+        match source {
+            Some(source) => printer.source(&corpus, &entry, &source)?,
+            None if opts.is_batch() && command.entry.is_none() => {
+                printer.missing()?;
+                code = 1;
+            }
+            None => code = 1,
+        }
+    }
+    Ok(code)
+}
+
+pub fn run_legacy(
+    opts: &CorpusOptions,
+    printer: &Printer,
+    legacy: &mut LegacyOptions,
+    profile: &Profile,
+) -> anyhow::Result<i32> {
+    let deprecations = [
+        (legacy.nearest, "--nearest", "corpus nearest"),
+        (legacy.create.is_some(), "--create", "corpus ensure"),
+        (legacy.source_path, "--source-path", "corpus source"),
+    ];
+    for (_, flag, replacement) in deprecations.iter().filter(|(used, ..)| *used) {
+        eprintln!("warning: `{}` is deprecated, use `{}`", flag, replacement);
+    }
+
+    legacy.nearest_options.apply(profile);
+    let nearest = legacy.nearest || profile.anchor == Anchor::Nearest;
+
+    let corpus = opts.build(legacy.source_path)?;
+    let source = opts.source_or_cwd()?;
+
+    let result = if nearest {
+        legacy
+            .nearest_options
+            .query(&corpus)
+            .find(source.as_path())
+            .map(|entry| (entry.path, entry.source_path))
+    } else {
+        Some((corpus.record_path(source.as_path())?, source))
+    };
+
+    if legacy.source_path {
+        // The result is (mistakenly, but compatibly) treated as an entry.
+        let result = result.and_then(|(entry, _)| {
+            let source = corpus.get_source_path(&entry)?;
+            Some((entry, source))
+        });
+        if let (Some((_, source)), Some(create_as)) = (&result, legacy.create) {
+            create_as.options().ensure(source)?;
+        }
+        match result {
+            Some((entry, source)) => printer.source(&corpus, &entry, &source)?,
+            None => printer.empty()?,
+        }
+    } else {
+        if let (Some((_, source)), Some(create_as)) = (&result, legacy.create) {
+            corpus.ensure(source.as_path(), &create_as.options())?;
+        }
+        match result {
+            Some((entry, source)) => printer.entry(&corpus, &entry, &source)?,
+            None => printer.empty()?,
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,89 @@
+use crate::options::RelativeToKind;
+use clap::Clap;
+use corpus::RootLocation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Where [`Profile`]s are read from, relative to the XDG config directory.
+const CONFIG_FILE: &str = "corpus/config.toml";
+
+/// Whether a profile resolves entries exactly, or finds the nearest one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    #[default]
+    Path,
+    Nearest,
+}
+
+/// A named set of defaults for the command line options.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub name: Option<String>,
+    pub kind: Option<String>,
+    pub ext: Option<String>,
+    pub relative_to: Option<PathBuf>,
+    pub relative_to_kind: Option<RelativeToKind>,
+    pub anchor: Anchor,
+    pub max_depth: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_at: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Config {
+    pub fn load() -> anyhow::Result<Self> {
+        let path = RootLocation::XDGConfig.path()?.join(CONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn profile(&mut self, name: &str) -> anyhow::Result<Profile> {
+        self.profiles
+            .remove(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown profile: {}", name))
+    }
+}
+
+#[derive(Clap, Debug)]
+pub enum ProfilesCommand {
+    /// Lists the names of the defined profiles.
+    List,
+
+    /// Shows the settings of a profile.
+    Show(ShowProfile),
+}
+
+#[derive(Clap, Debug)]
+pub struct ShowProfile {
+    pub profile: String,
+}
+
+pub fn run(command: &ProfilesCommand) -> anyhow::Result<i32> {
+    let mut config = Config::load()?;
+    let mut stdout = std::io::stdout();
+    match command {
+        ProfilesCommand::List => {
+            for name in config.profiles.keys() {
+                writeln!(stdout, "{}", name)?;
+            }
+        }
+        ProfilesCommand::Show(show) => {
+            let profile = config.profile(&show.profile)?;
+            write!(stdout, "{}", toml::to_string(&profile)?)?;
+        }
+    }
+    Ok(0)
+}
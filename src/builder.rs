@@ -1,22 +1,22 @@
-use crate::{root::RootLocation, Corpus, CorpusError};
+use crate::path::relative;
+use crate::{
+    normalize::Normalization, overflow::PathLimits, resolution::Resolution, rewrite::Rewrite,
+    root::RootLocation, Corpus, CorpusError,
+};
 use std::path::{Path, PathBuf};
 
+#[derive(Default)]
 pub struct CorpusBuilder {
     root_location: Option<RootLocation>,
     relative_path: Option<PathBuf>,
     name: Option<String>,
     extension: Option<String>,
-}
-
-impl Default for CorpusBuilder {
-    fn default() -> Self {
-        Self {
-            root_location: None,
-            relative_path: None,
-            name: None,
-            extension: None,
-        }
-    }
+    resolution: Resolution,
+    normalization: Normalization,
+    limits: PathLimits,
+    rewrites: Vec<Rewrite>,
+    relative_bases: Vec<(PathBuf, PathBuf)>,
+    dir_mode: Option<u32>,
 }
 
 /// Assists in building a [`crate::Corpus`] instance
@@ -39,6 +39,49 @@ impl CorpusBuilder {
         self
     }
 
+    /// Sets several "relative" directories, each mirrored into its own subtree.
+    ///
+    /// Each `(base, subtree)` pair maps input paths below `base` to the same
+    /// paths below `<root>/<subtree>`. When several bases contain an input
+    /// path, the longest one wins. This replaces any single "relative"
+    /// directory, so input paths outside of every base are treated the same
+    /// way as input paths outside of a single "relative" directory.
+    ///
+    /// Subtrees should be distinct, non-empty and not nested within one
+    /// another, so that [`crate::Corpus::get_source_path`] can pick the base
+    /// from the leading components of a "corpus" path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use corpus::builder;
+    ///
+    /// let corpus = builder()
+    ///     .with_root("/config")
+    ///     .relative_to_many(vec![("/home/me", "home"), ("/srv/checkouts", "srv")])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(corpus.path("/srv/checkouts/foo"), PathBuf::from("/config/srv/foo"));
+    /// assert_eq!(
+    ///     corpus.get_source_path("/config/home/bar"),
+    ///     Some(PathBuf::from("/home/me/bar"))
+    /// );
+    /// ```
+    pub fn relative_to_many<I, P, S>(mut self, bases: I) -> Self
+    where
+        I: IntoIterator<Item = (P, S)>,
+        P: Into<PathBuf>,
+        S: AsRef<Path>,
+    {
+        self.relative_bases = bases
+            .into_iter()
+            .map(|(base, subtree)| (base.into(), relative(subtree.as_ref())))
+            .collect();
+        self
+    }
+
     /// Sets the "relative" directory to the home directory.
     #[cfg(feature = "home")]
     pub fn relative_to_home(self) -> Result<Self, CorpusError> {
@@ -71,6 +114,67 @@ impl CorpusBuilder {
         self
     }
 
+    /// Sets how source paths are resolved before being mapped.
+    ///
+    /// Defaults to [`Resolution::Lexical`]. The chosen resolution also applies
+    /// to the "root" and "relative" directories themselves, as well as to
+    /// [`crate::Corpus::get_source_path`].
+    pub fn with_resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Normalizes source path components into a Unicode normalization form.
+    ///
+    /// Requires the "unicode" feature.
+    #[cfg(feature = "unicode")]
+    pub fn with_unicode_normalization(mut self, form: crate::UnicodeForm) -> Self {
+        self.normalization.unicode = Some(form);
+        self
+    }
+
+    /// Lower-cases source path components before mapping them.
+    ///
+    /// Useful when sources live on case-insensitive mounts, where `~/Foo`
+    /// and `~/foo` are the same directory.
+    ///
+    /// [`crate::Corpus::get_source_path`] still returns the casing the source
+    /// has on disk, as long as it exists.
+    pub fn with_case_folding(mut self) -> Self {
+        self.normalization.fold_case = true;
+        self
+    }
+
+    /// Sets the path length limits which generated paths must fit within.
+    ///
+    /// Defaults to the common Linux `PATH_MAX` and `NAME_MAX` values. Paths
+    /// which would exceed them are shortened, as described on
+    /// [`crate::Corpus::path`].
+    pub fn with_path_limits(mut self, limits: PathLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Adds a [`Rewrite`] rule, applied before mirroring source paths.
+    ///
+    /// Rules are tried in the order they were added, and the first which
+    /// applies to a source path wins. Rules take precedence over the
+    /// "relative" directory, both when mapping with [`crate::Corpus::path`]
+    /// and when inverting with [`crate::Corpus::get_source_path`].
+    pub fn with_rewrite(mut self, rewrite: Rewrite) -> Self {
+        self.rewrites.push(rewrite);
+        self
+    }
+
+    /// Sets the permissions of directories created for entries (e.g. `0o700`).
+    ///
+    /// Only applies on unix platforms, and is still subject to the umask.
+    /// Without it, directories are created with the platform default.
+    pub fn with_dir_mode(mut self, mode: u32) -> Self {
+        self.dir_mode = Some(mode);
+        self
+    }
+
     /// Builds the [`crate::Corpus`] instance given the builder configuration.
     pub fn build(self) -> Result<Corpus, CorpusError> {
         let root_location = {
@@ -88,7 +192,34 @@ impl CorpusBuilder {
             .relative_path
             .unwrap_or_else(|| Path::new("/").to_path_buf());
 
-        Ok(Corpus::new(root_location, relative_path, self.extension))
+        let resolution = self.resolution;
+        let normalization = self.normalization;
+        let mut corpus = Corpus::new(
+            resolution.resolve(&root_location),
+            resolution.resolve(&relative_path),
+            self.extension,
+        );
+        corpus.resolution = resolution;
+        corpus.normalization = normalization;
+        corpus.limits = self.limits;
+        corpus.dir_mode = self.dir_mode;
+        corpus.rewrites = self
+            .rewrites
+            .into_iter()
+            .map(|rewrite| {
+                rewrite
+                    .resolved(|path| resolution.resolve(path))
+                    .normalized(&normalization)
+            })
+            .collect();
+        // Subtrees and rewrite targets are normalized up front, as the
+        // entries below them are, so that `get_source_path` can match them.
+        corpus.relative_bases = self
+            .relative_bases
+            .into_iter()
+            .map(|(base, subtree)| (resolution.resolve(&base), normalization.path(&subtree)))
+            .collect();
+        Ok(corpus)
     }
 }
 
@@ -126,10 +257,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(corpus.root_location, PathBuf::from("/config/foo"));
-        assert_eq!(
-            corpus.relative_path,
-            PathBuf::from(dirs_next::home_dir().unwrap())
-        );
+        assert_eq!(corpus.relative_path, dirs_next::home_dir().unwrap());
         assert_eq!(corpus.extension, Some("txt".to_string()));
     }
 }
@@ -0,0 +1,105 @@
+use crate::{CorpusBuilder, PathLimits, Resolution, Rewrite, RootLocation};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A serializable description of a [`crate::Corpus`], for embedding into the
+/// config files of tools built on top of it.
+///
+/// Every field is optional, and maps onto the [`CorpusBuilder`] method of the
+/// same name. Use `CorpusBuilder::from` to turn it into a builder.
+///
+/// Requires the "serde" feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use corpus::{CorpusBuilder, CorpusConfig, RootLocation};
+///
+/// let config = CorpusConfig {
+///     root: Some(RootLocation::from("/config")),
+///     relative_to: Some(PathBuf::from("/home")),
+///     name: Some("project".to_string()),
+///     extension: Some("toml".to_string()),
+///     ..CorpusConfig::default()
+/// };
+///
+/// let corpus = CorpusBuilder::from(config).build().unwrap();
+/// assert_eq!(corpus.path("/home/foo"), PathBuf::from("/config/project/foo.toml"));
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorpusConfig {
+    /// See [`CorpusBuilder::with_root`].
+    pub root: Option<RootLocation>,
+
+    /// See [`CorpusBuilder::relative_to`].
+    pub relative_to: Option<PathBuf>,
+
+    /// See [`CorpusBuilder::relative_to_many`].
+    pub relative_bases: Vec<(PathBuf, PathBuf)>,
+
+    /// See [`CorpusBuilder::with_name`].
+    pub name: Option<String>,
+
+    /// See [`CorpusBuilder::with_extension`].
+    pub extension: Option<String>,
+
+    /// See [`CorpusBuilder::with_resolution`].
+    pub resolution: Resolution,
+
+    /// See [`CorpusBuilder::with_unicode_normalization`].
+    #[cfg(feature = "unicode")]
+    pub unicode: Option<crate::UnicodeForm>,
+
+    /// See [`CorpusBuilder::with_case_folding`].
+    pub fold_case: bool,
+
+    /// See [`CorpusBuilder::with_path_limits`].
+    pub limits: Option<PathLimits>,
+
+    /// See [`CorpusBuilder::with_rewrite`].
+    pub rewrites: Vec<Rewrite>,
+
+    /// See [`CorpusBuilder::with_dir_mode`].
+    pub dir_mode: Option<u32>,
+}
+
+impl From<CorpusConfig> for CorpusBuilder {
+    fn from(config: CorpusConfig) -> Self {
+        let mut builder = CorpusBuilder::default().with_resolution(config.resolution);
+
+        if let Some(root) = config.root {
+            builder = builder.with_root(root);
+        }
+        if let Some(relative_to) = config.relative_to {
+            builder = builder.relative_to(relative_to);
+        }
+        if !config.relative_bases.is_empty() {
+            builder = builder.relative_to_many(config.relative_bases);
+        }
+        if let Some(name) = config.name {
+            builder = builder.with_name(name);
+        }
+        if let Some(extension) = config.extension {
+            builder = builder.with_extension(extension);
+        }
+        #[cfg(feature = "unicode")]
+        if let Some(form) = config.unicode {
+            builder = builder.with_unicode_normalization(form);
+        }
+        if config.fold_case {
+            builder = builder.with_case_folding();
+        }
+        if let Some(limits) = config.limits {
+            builder = builder.with_path_limits(limits);
+        }
+        for rewrite in config.rewrites {
+            builder = builder.with_rewrite(rewrite);
+        }
+        if let Some(mode) = config.dir_mode {
+            builder = builder.with_dir_mode(mode);
+        }
+        builder
+    }
+}
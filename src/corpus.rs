@@ -1,13 +1,35 @@
-use crate::path::MaybePath;
+use crate::entry::{Ancestors, Collision, Descendants, Entry};
+use crate::nearest::NearestQuery;
+use crate::normalize::Normalization;
+use crate::overflow::{self, PathLimits};
+use crate::path::{join, MaybePath};
+use crate::resolution::Resolution;
+use crate::rewrite::Rewrite;
+use crate::CorpusError;
 
 use path_absolutize::Absolutize;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Corpus {
     pub root_location: PathBuf,
     pub relative_path: PathBuf,
     pub extension: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resolution: Resolution,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub normalization: Normalization,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub limits: PathLimits,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rewrites: Vec<Rewrite>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub relative_bases: Vec<(PathBuf, PathBuf)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dir_mode: Option<u32>,
 }
 
 impl Corpus {
@@ -27,6 +49,12 @@ impl Corpus {
             root_location,
             relative_path,
             extension,
+            resolution: Resolution::default(),
+            normalization: Normalization::default(),
+            limits: PathLimits::default(),
+            rewrites: Vec::new(),
+            relative_bases: Vec::new(),
+            dir_mode: None,
         }
     }
 
@@ -54,36 +82,289 @@ impl Corpus {
     ///
     /// assert_eq!(path, PathBuf::from("/home/.config/foo/bar/baz.toml"));
     /// ```
+    ///
+    /// The extension is appended to the source's name, unless the name
+    /// already ends with it, so `/home/proj.v2` maps to `proj.v2.toml`.
+    ///
+    /// If the resulting path would exceed the configured [`PathLimits`], the
+    /// overflowing tail is replaced by a deterministic `~<hash>` component.
+    /// This only computes the path: [`Corpus::get_source_path`] can only
+    /// resolve such an entry once its full source path has been recorded,
+    /// with [`Corpus::record_path`] or [`Corpus::prepare_path`].
     pub fn path<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> PathBuf {
-        let input = input.into().to_path_buf();
-        let path = input
-            .absolutize()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|_| input.clone());
+        self.map_source(&self.source(input))
+    }
 
-        let relative_path = path.strip_prefix(&self.relative_path).unwrap_or(&path);
+    /// Computes the "corpus" path for the `input` source path (as with
+    /// [`Corpus::path`]), and readies it to be created, by creating its
+    /// missing parent directories.
+    ///
+    /// If the path was shortened to fit the [`PathLimits`], the full source
+    /// path is also recorded in a hidden `.~<hash>.source` sidecar file next
+    /// to it, so that [`Corpus::get_source_path`] can still resolve it. The
+    /// helpers which create entries (e.g. [`Corpus::write_atomic`] and
+    /// [`Corpus::ensure`]) call this themselves.
+    pub fn prepare_path<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+    ) -> Result<PathBuf, CorpusError> {
+        let path = self.record_path(input)?;
+        self.create_parents(&path)?;
+        Ok(path)
+    }
 
-        let mut abs_path = self.root_location.join(relative_path);
-        if let Some(ext) = &self.extension {
-            abs_path = abs_path.with_extension(ext);
-        };
-        abs_path
+    /// Computes the "corpus" path for the `input` source path (as with
+    /// [`Corpus::path`]), recording the full source path if it was shortened
+    /// to fit the [`PathLimits`] (as with [`Corpus::prepare_path`]).
+    ///
+    /// Paths which fit are returned without touching the filesystem, so this
+    /// suits callers which hand the path on for someone else to create.
+    pub fn record_path<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+    ) -> Result<PathBuf, CorpusError> {
+        let source = self.source(input);
+        let path = self.map_source(&source);
+        if self.is_shortened(&path) {
+            self.create_parents(&path)?;
+            overflow::write_sidecar(&path, &source)?;
+        }
+        Ok(path)
     }
 
-    /// Returns the set of parent "corpus" directories which are upstream of the `input` Path.
-    pub fn ancestors<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> impl Iterator<Item = PathBuf> {
-        self.path(input)
-            .ancestors()
-            .filter(|p| self.is_ancestor(*p))
-            .map(|p| {
-                if let Some(ext) = &self.extension {
-                    p.with_extension(ext)
+    /// Computes the "corpus" paths for many `inputs` at once, in order.
+    ///
+    /// This is equivalent to calling [`Corpus::path`] on each input, but the
+    /// current directory which relative inputs are resolved against is only
+    /// looked up once, rather than once per input (the root and "relative"
+    /// directories are already resolved when the corpus is built).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use corpus::builder;
+    ///
+    /// let corpus = builder()
+    ///     .with_root("/home/.config")
+    ///     .relative_to("/home")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let paths = corpus.map_many(&["/home/foo", "/home/bar"]);
+    ///
+    /// assert_eq!(
+    ///     paths,
+    ///     vec![
+    ///         PathBuf::from("/home/.config/foo"),
+    ///         PathBuf::from("/home/.config/bar"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn map_many<I>(&self, inputs: I) -> Vec<PathBuf>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        let mut current_dir = None;
+        inputs
+            .into_iter()
+            .map(|input| {
+                let input = input.as_ref();
+                let source = if input.is_relative() {
+                    let current_dir =
+                        current_dir.get_or_insert_with(|| self.resolution.current_dir());
+                    self.resolution.resolve(&current_dir.join(input))
                 } else {
-                    p.to_path_buf()
-                }
+                    self.resolution.resolve(input)
+                };
+                self.map_source(&source)
             })
-            .collect::<Vec<PathBuf>>()
-            .into_iter()
+            .collect()
+    }
+
+    /// Resolves an `input` into the absolute source path used for mapping.
+    pub(crate) fn source<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> PathBuf {
+        match input.into() {
+            MaybePath::Path(path) => self.resolution.resolve(path),
+            MaybePath::CurrentDir => self.resolution.current_dir(),
+        }
+    }
+
+    /// Mirrors an already-resolved absolute `source` path into the root.
+    pub(crate) fn map_source(&self, source: &Path) -> PathBuf {
+        let relative_path = match self.relative_source(source) {
+            Some(relative_path) => self.normalization.path(&relative_path),
+            None => return self.with_extension(source.to_path_buf()),
+        };
+
+        let abs_path = if relative_path.as_os_str().is_empty() {
+            self.root_location.clone()
+        } else {
+            self.root_location.join(&relative_path)
+        };
+        let abs_path = self.with_extension(abs_path);
+
+        if self.limits.fits(&abs_path) {
+            abs_path
+        } else {
+            self.limits.shorten(
+                &self.root_location,
+                &relative_path,
+                self.extension.as_deref(),
+            )
+        }
+    }
+
+    /// Returns the path relative to the root which `source` is mirrored at,
+    /// or `None` if it is outside of every "relative" directory and every
+    /// [`Rewrite`] rule.
+    pub(crate) fn relative_source(&self, source: &Path) -> Option<PathBuf> {
+        if let Some(relative_path) = self
+            .rewrites
+            .iter()
+            .find_map(|rewrite| rewrite.apply(source))
+        {
+            return Some(relative_path);
+        }
+
+        let (base, subtree) = self.base_of(source)?;
+        let remainder = source.strip_prefix(base).ok()?;
+        Some(join(subtree, remainder))
+    }
+
+    /// Returns the directory which an absolute `source` path is mirrored
+    /// from: the part of it matched by a [`Rewrite`] rule, or otherwise its
+    /// "relative" directory. Returns `None` if it is outside of all of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    /// use corpus::{builder, Rewrite};
+    ///
+    /// let corpus = builder()
+    ///     .with_root("/config")
+    ///     .relative_to_many(vec![("/home/me", "home"), ("/srv/checkouts", "srv")])
+    ///     .with_rewrite(Rewrite::glob("/mnt/*/work", "work"))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let base = corpus.relative_base(Path::new("/srv/checkouts/foo"));
+    /// assert_eq!(base, Some(PathBuf::from("/srv/checkouts")));
+    /// let base = corpus.relative_base(Path::new("/mnt/disk/work/foo"));
+    /// assert_eq!(base, Some(PathBuf::from("/mnt/disk/work")));
+    /// assert_eq!(corpus.relative_base(Path::new("/opt/foo")), None);
+    /// ```
+    pub fn relative_base(&self, source: &Path) -> Option<PathBuf> {
+        if let Some(matched) = self
+            .rewrites
+            .iter()
+            .find_map(|rewrite| rewrite.matched(source))
+        {
+            return Some(matched);
+        }
+        self.base_of(source).map(|(base, _)| base.to_path_buf())
+    }
+
+    /// Returns the longest base containing `source`, with its subtree.
+    fn base_of(&self, source: &Path) -> Option<(&Path, &Path)> {
+        self.bases()
+            .filter(|(base, _)| source.starts_with(base))
+            .max_by_key(|(base, _)| base.components().count())
+    }
+
+    /// Returns each "relative" directory, paired with the subtree of the
+    /// root it is mirrored into.
+    ///
+    /// Without any `relative_bases`, this is just the `relative_path`
+    /// mirrored directly into the root.
+    pub(crate) fn bases(&self) -> impl Iterator<Item = (&Path, &Path)> {
+        let single = if self.relative_bases.is_empty() {
+            Some((self.relative_path.as_path(), Path::new("")))
+        } else {
+            None
+        };
+        single.into_iter().chain(
+            self.relative_bases
+                .iter()
+                .map(|(base, subtree)| (base.as_path(), subtree.as_path())),
+        )
+    }
+
+    /// Appends the extension to the file name of `path`, rather than
+    /// replacing any extension it already has, so that e.g. `proj` and
+    /// `proj.v2` map to distinct entries. A name which already carries the
+    /// extension is left as it is.
+    fn with_extension(&self, path: PathBuf) -> PathBuf {
+        let ext = match &self.extension {
+            Some(ext) if path.extension() != Some(OsStr::new(ext)) => ext,
+            _ => return path,
+        };
+        match path.file_name() {
+            Some(name) => {
+                let mut name = name.to_os_string();
+                name.push(".");
+                name.push(ext);
+                path.with_file_name(name)
+            }
+            None => path,
+        }
+    }
+
+    /// Returns `true` if `path` is a "corpus" path shortened to fit the [`PathLimits`].
+    fn is_shortened(&self, path: &Path) -> bool {
+        path.starts_with(&self.root_location) && overflow::sidecar(path).is_some()
+    }
+
+    /// Returns the entries for the `input` path and each of its parents.
+    ///
+    /// Each parent source directory is mapped through [`Corpus::path`] in
+    /// its own right, so with an extension every yielded path is that
+    /// directory's own entry (e.g. `proj.v2.toml` for a `proj.v2` directory,
+    /// distinct from the `proj.toml` of a `proj` directory). The walk stops at the "relative" directory,
+    /// whose entry is the root itself. Inputs outside of the "relative"
+    /// directory have no ancestors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use corpus::builder;
+    ///
+    /// let corpus = builder()
+    ///     .with_root("/home/.config")
+    ///     .relative_to("/home")
+    ///     .with_name("foo")
+    ///     .with_extension("toml")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let paths: Vec<PathBuf> = corpus
+    ///     .ancestors("/home/bar/baz")
+    ///     .rev()
+    ///     .map(|entry| entry.path)
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     paths,
+    ///     vec![
+    ///         PathBuf::from("/home/.config/foo.toml"),
+    ///         PathBuf::from("/home/.config/foo/bar.toml"),
+    ///         PathBuf::from("/home/.config/foo/bar/baz.toml"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn ancestors<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Ancestors<'_> {
+        Ancestors::new(self, self.source(input))
+    }
+
+    /// Returns the existing entries for source paths below the `input` path.
+    ///
+    /// The `input` path itself is not included. See [`Descendants`] for
+    /// which paths are considered entries.
+    pub fn descendants<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Descendants<'_> {
+        Descendants::new(self, &self.source(input))
     }
 
     /// Finds the nearest ancestor [`Corpus::path`] which actually exists.
@@ -92,11 +373,72 @@ impl Corpus {
     ///   exists, return that path
     /// - If that path does not exist but `/some/path/foo` does, return that path
     ///   instead.
-    /// - Continue traversing upwards until hitting the `relative_path`
+    /// - Continue traversing upwards until hitting the `relative_path`, whose
+    ///   entry is the root entry (e.g. `/some/path.toml` with an extension, or
+    ///   `/some/path` itself without one).
     pub fn find_nearest<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Option<PathBuf> {
-        self.ancestors(input)
-            .filter(|p| p.strip_prefix(&self.root_location).is_ok())
-            .find(|p| p.exists())
+        self.nearest().find(input).map(|entry| entry.path)
+    }
+
+    /// Returns every existing entry in the corpus.
+    ///
+    /// The root entry (the entry for the "relative" directory itself) comes
+    /// first when it exists, followed by the [`Corpus::descendants`] of the
+    /// "relative" directory. With several `relative_bases`, this repeats for
+    /// each base in turn.
+    pub fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
+        self.bases().flat_map(move |(base, _)| {
+            let root_entry = self.map_source(base);
+            let root_entry = if root_entry.exists() {
+                Some(Entry {
+                    path: root_entry,
+                    source_path: base.to_path_buf(),
+                    depth: 0,
+                })
+            } else {
+                None
+            };
+            root_entry.into_iter().chain(self.descendants(base))
+        })
+    }
+
+    /// Reports existing entries which collide under the active [`Normalization`].
+    ///
+    /// Entries created before a normalization was enabled (or by other tools)
+    /// may differ only in letter case or Unicode normalization, in which case
+    /// [`Corpus::path`] can only ever produce one of them. Each returned
+    /// [`Collision`] groups the entries which normalize to the same path.
+    pub fn collisions(&self) -> Vec<Collision> {
+        let mut groups: BTreeMap<PathBuf, Vec<Entry>> = BTreeMap::new();
+        for entry in self.entries() {
+            let path = match entry.path.strip_prefix(&self.root_location) {
+                Ok(relative_path) => self
+                    .root_location
+                    .join(self.normalization.path(relative_path)),
+                Err(_) => entry.path.clone(),
+            };
+            groups.entry(path).or_default().push(entry);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .map(|(path, entries)| Collision { path, entries })
+            .collect()
+    }
+
+    /// Returns the "relative" directory whose own entry is `path`, if any.
+    fn root_entry_source(&self, path: &Path) -> Option<&Path> {
+        self.bases()
+            .map(|(base, _)| base)
+            .find(|base| self.map_source(base) == path)
+    }
+
+    /// Starts a configurable search for the nearest existing ancestor entry.
+    ///
+    /// See [`NearestQuery`] for the available options.
+    pub fn nearest(&self) -> NearestQuery<'_> {
+        NearestQuery::new(self)
     }
 
     /// Gets the concrete path which corresponds to an input corpus `path`.
@@ -105,6 +447,13 @@ impl Corpus {
     /// [`Corpus::path`] method), and returns the corresponding path
     /// (essentially the input of the [`Corpus::path`] method).
     ///
+    /// Where source components are normalized (e.g. with
+    /// [`crate::CorpusBuilder::with_case_folding`]), their original spelling
+    /// is recovered from the source directories which still exist.
+    ///
+    /// A path shortened to fit the [`PathLimits`] only resolves once its
+    /// source has been recorded (see [`Corpus::record_path`]).
+    ///
     /// # Examples
     ///
     /// ```
@@ -132,13 +481,39 @@ impl Corpus {
     /// assert_eq!(path, PathBuf::from("/root/foo/bar/baz"));
     /// ```
     pub fn get_source_path<P: Into<PathBuf>>(&self, path: P) -> Option<PathBuf> {
-        let path = path.into();
-        self.relative_path
-            .join(path.strip_prefix(&self.root_location).unwrap_or(&path))
-            .with_extension("")
-            .absolutize()
-            .map(|p| p.to_path_buf())
-            .ok()
+        let path = self.resolution.resolve(&path.into());
+        if let Some(base) = self.root_entry_source(&path) {
+            return absolutize(base);
+        }
+
+        let relative_path = match path.strip_prefix(&self.root_location) {
+            Ok(relative_path) => relative_path,
+            Err(_) => return absolutize(&path.with_extension("")),
+        };
+
+        if let Some(source) = overflow::read_sidecar(&self.root_location, relative_path) {
+            return Some(source);
+        }
+        // A shortened path can't be mapped back without its sidecar.
+        if overflow::is_shortened(relative_path) {
+            return None;
+        }
+
+        let relative_path = relative_path.with_extension("");
+        if let Some(source) = self
+            .rewrites
+            .iter()
+            .find_map(|rewrite| rewrite.invert(&relative_path))
+        {
+            return absolutize(&self.normalization.restore(&source));
+        }
+
+        let (base, subtree) = self
+            .bases()
+            .filter(|(_, subtree)| relative_path.starts_with(subtree))
+            .max_by_key(|(_, subtree)| subtree.components().count())?;
+        let remainder = relative_path.strip_prefix(subtree).ok()?;
+        absolutize(&self.normalization.restore(&join(base, remainder)))
     }
 
     /// Returns `true` if the input `path` is relative to the "root".
@@ -166,12 +541,14 @@ impl Corpus {
     /// assert_eq!(path, true);
     /// ```
     pub fn is_ancestor<'a, P: Into<&'a Path>>(&self, path: P) -> bool {
-        let path = path.into();
-        if let Some(ext) = &self.extension {
-            if self.root_location.with_extension(ext) == path {
-                return true;
-            }
+        let path = self.resolution.resolve(path.into());
+        if self.root_entry_source(&path).is_some() {
+            return true;
         }
-        return path.strip_prefix(&self.root_location).is_ok();
+        path.strip_prefix(&self.root_location).is_ok()
     }
 }
+
+fn absolutize(path: &Path) -> Option<PathBuf> {
+    path.absolutize().map(|p| p.to_path_buf()).ok()
+}
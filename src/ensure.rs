@@ -0,0 +1,224 @@
+use crate::fs::{copy, remove};
+use crate::lock::LockMode;
+use crate::path::MaybePath;
+use crate::{Corpus, CorpusError};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What [`Corpus::ensure`] creates a missing entry as.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum EntryKind {
+    /// An empty directory.
+    #[default]
+    Dir,
+
+    /// An empty file.
+    File,
+}
+
+/// The options for creating a missing entry with [`Corpus::ensure`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnsureOptions {
+    pub kind: EntryKind,
+
+    /// The permissions of the entry itself (e.g. `0o700`), where supported.
+    pub mode: Option<u32>,
+
+    /// A directory or file which the entry is created as a copy of.
+    pub template: Option<PathBuf>,
+
+    /// A shell command which initializes the entry, with `{}` standing in
+    /// for its path.
+    pub init: Option<String>,
+}
+
+impl EnsureOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the entry as an empty file, rather than as a directory.
+    pub fn as_file(mut self) -> Self {
+        self.kind = EntryKind::File;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Creates the entry as a copy of the `template` directory or file
+    /// (which takes precedence over the [`EntryKind`]).
+    pub fn with_template<P: Into<PathBuf>>(mut self, template: P) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// Runs `command` (through `sh -c`, or `cmd /C` on windows) once the
+    /// entry has been created, with `{}` replaced by its path, e.g.
+    /// `python -m venv {}`. The placeholder may be quoted or not.
+    pub fn with_init<S: Into<String>>(mut self, command: S) -> Self {
+        self.init = Some(command.into());
+        self
+    }
+
+    /// Creates `path` (and any missing parents) as described by the options,
+    /// unless it already exists.
+    ///
+    /// This is the unlocked core of [`Corpus::ensure`], for paths which
+    /// aren't entries. If copying the template or running the initializer
+    /// fails, `path` is removed again.
+    pub fn ensure(&self, path: &Path) -> Result<(), CorpusError> {
+        if path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        if let Err(error) = create(path, self) {
+            let _ = remove(path);
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+impl Corpus {
+    /// Creates the entry for the `input` source path, unless it already
+    /// exists, and returns its path.
+    ///
+    /// The entry is created while holding an exclusive [`Corpus::lock`] on it,
+    /// so that concurrent callers wait for it to be fully initialized. If
+    /// copying the template or running the initializer fails, the entry is
+    /// removed again, so that a later call can retry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use corpus::{builder, EnsureOptions};
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to("/home")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let options = EnsureOptions::new().with_mode(0o700);
+    /// let path = corpus.ensure("/home/foo", &options).unwrap();
+    /// assert!(path.is_dir());
+    ///
+    /// // Ensuring it again leaves the existing entry alone.
+    /// assert_eq!(corpus.ensure("/home/foo", &options).unwrap(), path);
+    /// ```
+    pub fn ensure<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+        options: &EnsureOptions,
+    ) -> Result<PathBuf, CorpusError> {
+        let source = self.source(input);
+        let path = self.prepare_path(source.as_path())?;
+
+        let _lock = self.lock(source.as_path(), LockMode::Exclusive)?;
+        options.ensure(&path)?;
+        Ok(path)
+    }
+}
+
+fn create(path: &Path, options: &EnsureOptions) -> Result<(), CorpusError> {
+    match &options.template {
+        Some(template) => copy(template, path)?,
+        None => match options.kind {
+            EntryKind::Dir => std::fs::create_dir(path)?,
+            EntryKind::File => {
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(path)?;
+            }
+        },
+    }
+
+    #[cfg(unix)]
+    if let Some(mode) = options.mode {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+
+    if let Some(init) = &options.init {
+        run_init(init, path)?;
+    }
+    Ok(())
+}
+
+/// Runs the `init` command, passing `path` as `$1` rather than splicing it
+/// into the command, so that it needn't be quoted.
+#[cfg(unix)]
+fn run_init(init: &str, path: &Path) -> Result<(), CorpusError> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(substitute_path(init))
+        .arg("sh")
+        .arg(path)
+        .status()?;
+    check_init(init, status)
+}
+
+/// Replaces each `{}` in the `init` command with a reference to `$1`, which
+/// expands to the path as a single word wherever the placeholder is quoted:
+/// `"$1"` when unquoted, `$1` within double quotes, and `'"$1"'` (closing
+/// and reopening the quotes) within single quotes.
+#[cfg(unix)]
+fn substitute_path(init: &str) -> String {
+    let mut command = String::with_capacity(init.len());
+    let (mut single, mut double) = (false, false);
+    let mut chars = init.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'}') => {
+                chars.next();
+                command.push_str(match (single, double) {
+                    (true, _) => "'\"$1\"'",
+                    (_, true) => "$1",
+                    _ => "\"$1\"",
+                });
+            }
+            '\\' if !single => {
+                command.push(c);
+                if let Some(next) = chars.next() {
+                    command.push(next);
+                }
+            }
+            '\'' if !double => {
+                single = !single;
+                command.push(c);
+            }
+            '"' if !single => {
+                double = !double;
+                command.push(c);
+            }
+            _ => command.push(c),
+        }
+    }
+    command
+}
+
+#[cfg(not(unix))]
+fn run_init(init: &str, path: &Path) -> Result<(), CorpusError> {
+    let command = init.replace("{}", &format!("\"{}\"", path.display()));
+    let status = Command::new("cmd").arg("/C").arg(command).status()?;
+    check_init(init, status)
+}
+
+fn check_init(init: &str, status: std::process::ExitStatus) -> Result<(), CorpusError> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(CorpusError::Init {
+            command: init.to_string(),
+            status,
+        })
+    }
+}
@@ -0,0 +1,245 @@
+use crate::Corpus;
+use crate::{link, lock, overflow};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// A single "corpus" entry, paired with the source path it corresponds to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The "corpus" path (the output of [`Corpus::path`]).
+    pub path: PathBuf,
+
+    /// The source path which maps to `path`.
+    pub source_path: PathBuf,
+
+    /// The distance between `source_path` and the path the walk started from.
+    pub depth: usize,
+}
+
+/// A set of existing entries which map to the same normalized path.
+///
+/// Produced by [`Corpus::collisions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision {
+    /// The normalized "corpus" path shared by each of the `entries`.
+    pub path: PathBuf,
+
+    /// The colliding entries, in the order they were walked.
+    pub entries: Vec<Entry>,
+}
+
+/// A lazy iterator over the entries for a source path and each of its parents.
+///
+/// Produced by [`Corpus::ancestors`]. Iterating forwards yields the entry for
+/// the input path first and ends at the entry for the "relative" directory;
+/// iterating backwards yields them in the opposite order.
+#[derive(Debug)]
+pub struct Ancestors<'a> {
+    corpus: &'a Corpus,
+    source: PathBuf,
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Ancestors<'a> {
+    pub(crate) fn new(corpus: &'a Corpus, source: PathBuf) -> Self {
+        let back = source
+            .ancestors()
+            .take_while(|ancestor| corpus.relative_source(ancestor).is_some())
+            .count();
+        Self {
+            corpus,
+            source,
+            front: 0,
+            back,
+        }
+    }
+
+    fn entry(&self, depth: usize) -> Option<Entry> {
+        let source_path = self.source.ancestors().nth(depth)?.to_path_buf();
+        Some(Entry {
+            path: self.corpus.map_source(&source_path),
+            source_path,
+            depth,
+        })
+    }
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let entry = self.entry(self.front);
+        self.front += 1;
+        entry
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back.saturating_sub(self.front);
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Ancestors<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.entry(self.back)
+    }
+}
+
+impl<'a> ExactSizeIterator for Ancestors<'a> {}
+
+/// A lazy, depth-first iterator over the existing entries below a source path.
+///
+/// Produced by [`Corpus::descendants`]. Entries within a single directory
+/// are yielded in sorted order, and each directory is only read once the
+/// walk reaches it.
+///
+/// When the corpus has an extension, only paths with that extension are
+/// considered entries, and entries are not descended into (e.g. a `.git`
+/// directory entry is not walked).
+///
+/// Without an extension, an entry's own contents can't be told apart from
+/// the entries nested below it by name alone, so an entry ends where its
+/// source does:
+///
+/// - Directories are only walked while their source paths exist, so nothing
+///   below an entry whose source is missing is yielded.
+/// - Directly within the directory a "relative" directory is mirrored into,
+///   every path is an entry, whether or not its source still exists.
+/// - Within any other entry (e.g. a `.venv` or an adopted `node_modules`),
+///   only paths whose sources exist are nested entries; everything else is
+///   the entry's own data, and is never reported as orphaned.
+#[derive(Debug)]
+pub struct Descendants<'a> {
+    corpus: &'a Corpus,
+    stack: Vec<Frame>,
+}
+
+/// A directory being walked by [`Descendants`].
+#[derive(Debug)]
+struct Frame {
+    depth: usize,
+    children: std::vec::IntoIter<PathBuf>,
+
+    /// Whether the directory is an entry whose source exists, so that its
+    /// children without sources are its data rather than orphaned entries.
+    is_entry: bool,
+}
+
+impl<'a> Descendants<'a> {
+    pub(crate) fn new(corpus: &'a Corpus, source: &Path) -> Self {
+        let mut descendants = Self {
+            corpus,
+            stack: Vec::new(),
+        };
+
+        // The directory holding the entries below `source`. Unlike its entry
+        // (`map_source`), this never carries the extension.
+        if let Some(relative_path) = corpus.relative_source(source) {
+            let dir = corpus
+                .root_location
+                .join(corpus.normalization.path(&relative_path));
+            let is_base = corpus.bases().any(|(base, _)| base == source);
+            descendants.push_dir(&dir, 1, !is_base && source.exists());
+        }
+        descendants
+    }
+
+    fn push_dir(&mut self, dir: &Path, depth: usize, is_entry: bool) {
+        let mut children: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| !is_bookkeeping(path))
+                .collect(),
+            Err(_) => return,
+        };
+        // Order by the source path each child maps to, so that a directory's
+        // own entry (`bar.toml`) precedes the entries nested within it (`bar/`).
+        children.sort_by_cached_key(|child| {
+            let is_entry = self.is_entry(child);
+            let key = if is_entry && self.corpus.extension.is_some() {
+                child.with_extension("")
+            } else {
+                child.clone()
+            };
+            (key, !is_entry)
+        });
+        self.stack.push(Frame {
+            depth,
+            children: children.into_iter(),
+            is_entry,
+        });
+    }
+
+    fn is_entry(&self, path: &Path) -> bool {
+        match &self.corpus.extension {
+            Some(ext) => path.extension() == Some(OsStr::new(ext)),
+            None => true,
+        }
+    }
+}
+
+/// Returns `true` for the files the corpus keeps for itself, which are
+/// neither entries nor an entry's data.
+fn is_bookkeeping(path: &Path) -> bool {
+    overflow::is_sidecar(path) || lock::is_lock_file(path) || link::is_manifest(path)
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            let (depth, in_entry) = (frame.depth, frame.is_entry);
+            let path = match frame.children.next() {
+                Some(path) => path,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+
+            if self.corpus.extension.is_some() {
+                let is_entry = self.is_entry(&path);
+                if path.is_dir() && !is_entry {
+                    self.push_dir(&path, depth + 1, false);
+                }
+                if is_entry {
+                    if let Some(source_path) = self.corpus.get_source_path(&path) {
+                        return Some(Entry {
+                            path,
+                            source_path,
+                            depth,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            let source_path = match self.corpus.get_source_path(&path) {
+                Some(source_path) => source_path,
+                None => continue,
+            };
+            let source_exists = source_path.exists();
+            if !source_exists && in_entry {
+                continue;
+            }
+            if source_exists && path.is_dir() {
+                self.push_dir(&path, depth + 1, true);
+            }
+            return Some(Entry {
+                path,
+                source_path,
+                depth,
+            });
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::path::MaybePath;
+use crate::{Corpus, CorpusError};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Helpers for using the "corpus" entries as a small per-directory data store.
+///
+/// Each helper takes a source path (like [`Corpus::path`]) rather than the
+/// "corpus" path itself, and readies the entry with [`Corpus::prepare_path`]
+/// (creating any missing parent directories using [`Corpus::dir_mode`],
+/// where supported) before writing.
+impl Corpus {
+    /// Reads the entry for the `input` source path.
+    pub fn read<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Result<Vec<u8>, CorpusError> {
+        Ok(std::fs::read(self.path(input))?)
+    }
+
+    /// Reads the entry for the `input` source path into a string.
+    pub fn read_to_string<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+    ) -> Result<String, CorpusError> {
+        Ok(std::fs::read_to_string(self.path(input))?)
+    }
+
+    /// Atomically replaces the entry for the `input` source path with `contents`.
+    ///
+    /// The contents are written to a temporary file next to the entry, which
+    /// is synced to disk and then renamed over the entry. Readers therefore
+    /// observe either the old or the new contents, never a partial write.
+    ///
+    /// Returns the path of the written entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to("/home")
+    ///     .with_extension("toml")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let path = corpus.write_atomic("/home/foo/bar", "key = 1").unwrap();
+    /// assert_eq!(path, root.path().join("foo/bar.toml"));
+    /// assert_eq!(corpus.read_to_string("/home/foo/bar").unwrap(), "key = 1");
+    /// ```
+    pub fn write_atomic<'a, I: Into<MaybePath<'a>>, C: AsRef<[u8]>>(
+        &self,
+        input: I,
+        contents: C,
+    ) -> Result<PathBuf, CorpusError> {
+        let path = self.prepare_path(input)?;
+        write_atomic(&path, contents.as_ref())?;
+        Ok(path)
+    }
+
+    /// Opens the entry for the `input` source path for reading and writing,
+    /// creating it (but never truncating it) if necessary.
+    pub fn open_or_create<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+    ) -> Result<File, CorpusError> {
+        let path = self.prepare_path(input)?;
+
+        Ok(OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?)
+    }
+
+    /// Creates the missing parent directories of a "corpus" `path`.
+    pub(crate) fn create_parents(&self, path: &Path) -> Result<(), CorpusError> {
+        match path.parent() {
+            Some(parent) => self.create_dir_all(parent),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates `dir` and any missing parents, using the configured mode.
+    pub(crate) fn create_dir_all(&self, dir: &Path) -> Result<(), CorpusError> {
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+
+        #[cfg(unix)]
+        if let Some(mode) = self.dir_mode {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(mode);
+        }
+
+        Ok(builder.create(dir)?)
+    }
+}
+
+/// Atomically replaces the file at `path` with `contents`, via a synced
+/// temporary file renamed over it.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp_path = temp_path(path);
+    let result = write_synced(&temp_path, contents).and_then(|_| std::fs::rename(&temp_path, path));
+    if let Err(error) = result {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    sync_parent(path)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let unique = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        unique
+    ))
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Syncs the directory containing `path`, so that a rename into it is durable.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) => File::open(parent)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Recursively copies the `from` directory or file to `to`, preserving
+/// symlinks and directory permissions.
+pub(crate) fn copy(from: &Path, to: &Path) -> std::io::Result<()> {
+    let file_type = std::fs::symlink_metadata(from)?.file_type();
+    if file_type.is_dir() {
+        std::fs::create_dir(to)?;
+        std::fs::set_permissions(to, std::fs::metadata(from)?.permissions())?;
+        for child in std::fs::read_dir(from)? {
+            let child = child?;
+            copy(&child.path(), &to.join(child.file_name()))?;
+        }
+    } else if file_type.is_symlink() {
+        symlink(&std::fs::read_link(from)?, to)?;
+    } else {
+        std::fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Moves `from` to `to`, falling back to copying (and then removing) it when
+/// they are on different filesystems.
+pub(crate) fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            copy(from, to)?;
+            remove(from)
+        }
+        result => result,
+    }
+}
+
+/// Removes the `path` directory (recursively) or file.
+pub(crate) fn remove(path: &Path) -> std::io::Result<()> {
+    if std::fs::symlink_metadata(path)?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Links to a `target` which doesn't exist (yet) are assumed to be for a
+/// directory, as entries usually are.
+#[cfg(windows)]
+pub(crate) fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    if target.is_file() {
+        std::os::windows::fs::symlink_file(target, link)
+    } else {
+        std::os::windows::fs::symlink_dir(target, link)
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    std::fs::remove_file(link)
+}
+
+#[cfg(windows)]
+pub(crate) fn remove_symlink(link: &Path) -> std::io::Result<()> {
+    // Directory symlinks are removed as directories on windows.
+    std::fs::remove_file(link).or_else(|_| std::fs::remove_dir(link))
+}
@@ -0,0 +1,68 @@
+use crate::path::MaybePath;
+use crate::Corpus;
+use std::path::PathBuf;
+
+/// A git repository whose git dir is kept in the corpus, apart from its work
+/// tree.
+///
+/// With the git dir at the "corpus" path of the work tree, git is pointed at
+/// it through `GIT_DIR` and `GIT_WORK_TREE` (see [`GitRepo::env`]), instead
+/// of through a `.git` directory in the work tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitRepo {
+    pub git_dir: PathBuf,
+    pub work_tree: PathBuf,
+}
+
+impl GitRepo {
+    /// The environment variables which point git at the repository.
+    pub fn env(&self) -> [(&'static str, &PathBuf); 2] {
+        [
+            ("GIT_DIR", &self.git_dir),
+            ("GIT_WORK_TREE", &self.work_tree),
+        ]
+    }
+}
+
+impl Corpus {
+    /// The repository for the `input` source path itself, whether or not it
+    /// exists yet.
+    pub fn git_repo<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> GitRepo {
+        let work_tree = self.source(input);
+        GitRepo {
+            git_dir: self.path(work_tree.as_path()),
+            work_tree,
+        }
+    }
+
+    /// Finds the repository enclosing the `input` source path: the nearest
+    /// ancestor entry which is a git dir (i.e. contains a `HEAD`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to("/home")
+    ///     .with_extension("git")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// std::fs::create_dir_all(root.path().join("foo.git")).unwrap();
+    /// std::fs::write(root.path().join("foo.git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    ///
+    /// let repo = corpus.find_git_repo("/home/foo/bar").unwrap();
+    /// assert_eq!(repo.git_dir, root.path().join("foo.git"));
+    /// assert_eq!(repo.work_tree, std::path::PathBuf::from("/home/foo"));
+    /// ```
+    pub fn find_git_repo<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Option<GitRepo> {
+        let entry = self.nearest().is_dir().contains("HEAD").find(input)?;
+        Some(GitRepo {
+            git_dir: entry.path,
+            work_tree: entry.source_path,
+        })
+    }
+}
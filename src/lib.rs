@@ -1,13 +1,43 @@
 #![doc = include_str!("../README.md")]
 
+mod adopt;
 mod builder;
+#[cfg(feature = "serde")]
+mod config;
 mod corpus;
+mod ensure;
+mod entry;
+mod fs;
+mod git;
+mod link;
+mod lock;
+mod nearest;
+mod normalize;
+mod overflow;
 mod path;
+mod resolution;
+mod rewrite;
 mod root;
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml", feature = "ron"))]
+mod store;
 
 pub use crate::builder::CorpusBuilder;
+#[cfg(feature = "serde")]
+pub use crate::config::CorpusConfig;
 pub use crate::corpus::Corpus;
-pub use crate::path::MaybePath;
+pub use crate::ensure::{EnsureOptions, EntryKind};
+pub use crate::entry::{Ancestors, Collision, Descendants, Entry};
+pub use crate::git::GitRepo;
+pub use crate::link::Link;
+pub use crate::lock::{EntryLock, LockMode};
+pub use crate::nearest::NearestQuery;
+pub use crate::normalize::Normalization;
+#[cfg(feature = "unicode")]
+pub use crate::normalize::UnicodeForm;
+pub use crate::overflow::PathLimits;
+pub use crate::path::{path_from_bytes, path_to_bytes, MaybePath};
+pub use crate::resolution::Resolution;
+pub use crate::rewrite::Rewrite;
 pub use crate::root::RootLocation;
 
 #[derive(thiserror::Error, Debug)]
@@ -17,6 +47,37 @@ pub enum CorpusError {
 
     #[error("Current directory does not exist or insufficient permissions")]
     InvalidCurrentDir,
+
+    #[error("Failed to access corpus entry: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unsupported corpus entry format: {0:?}")]
+    UnsupportedFormat(Option<String>),
+
+    #[error("Failed to deserialize corpus entry {path:?}: {message}")]
+    Deserialize {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("Failed to serialize corpus entry: {0}")]
+    Serialize(String),
+
+    #[error("Expected a single file name, not {0:?}")]
+    InvalidName(std::path::PathBuf),
+
+    #[error("Refusing to move {from:?} to {to:?}: {reason}")]
+    Move {
+        from: std::path::PathBuf,
+        to: std::path::PathBuf,
+        reason: &'static str,
+    },
+
+    #[error("Failed to initialize corpus entry with `{command}`: {status}")]
+    Init {
+        command: String,
+        status: std::process::ExitStatus,
+    },
 }
 
 /// Shorthand for constructing an empty builder.
@@ -0,0 +1,244 @@
+use crate::fs::{remove_symlink, symlink};
+use crate::path::{file_name, path_from_bytes, path_to_bytes, MaybePath};
+use crate::{Corpus, CorpusError};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// The file in the root which records the links created by [`Corpus::link`].
+const MANIFEST: &str = ".corpus-links";
+
+/// A symlink in a source directory, pointing at the directory's entry.
+///
+/// Such links let tools which can't be configured to use the corpus (e.g.
+/// those expecting a `.venv` in the project) find the entry anyway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    /// The symlink itself, e.g. `~/project/.venv`.
+    pub path: PathBuf,
+
+    /// The entry it points at.
+    pub entry: PathBuf,
+}
+
+impl Link {
+    /// Whether the link is (still) a symlink to its entry.
+    pub fn is_linked(&self) -> bool {
+        std::fs::read_link(&self.path).is_ok_and(|target| target == self.entry)
+    }
+}
+
+impl Corpus {
+    /// Creates a `name` symlink in the `input` source directory pointing at
+    /// its entry, and records it in a manifest in the root, so that it can be
+    /// found by [`Corpus::links`].
+    ///
+    /// Nothing is linked, and `None` is returned, if something other than
+    /// such a symlink (e.g. a real file) is already there. The entry itself
+    /// needn't exist yet. `name` must be a single file name, so that the link
+    /// stays within the source directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let source = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to(source.path())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let project = source.path().join("project");
+    /// std::fs::create_dir(&project).unwrap();
+    ///
+    /// let link = corpus.link(project.as_path(), ".venv").unwrap().unwrap();
+    /// assert_eq!(link.entry, root.path().join("project"));
+    /// assert_eq!(std::fs::read_link(project.join(".venv")).unwrap(), link.entry);
+    /// assert_eq!(corpus.links().unwrap(), vec![link]);
+    /// # }
+    /// ```
+    pub fn link<'a, I: Into<MaybePath<'a>>, N: AsRef<Path>>(
+        &self,
+        input: I,
+        name: N,
+    ) -> Result<Option<Link>, CorpusError> {
+        let source = self.source(input);
+        let link = Link {
+            path: source.join(file_name(name.as_ref())?),
+            entry: self.path(source.as_path()),
+        };
+
+        match std::fs::symlink_metadata(&link.path) {
+            Ok(_) if !link.is_linked() => return Ok(None),
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => symlink(&link.entry, &link.path)?,
+            Err(e) => return Err(e.into()),
+        }
+
+        self.update_manifest(|paths| {
+            if !paths.contains(&link.path) {
+                paths.push(link.path.clone());
+            }
+        })?;
+        Ok(Some(link))
+    }
+
+    /// The links recorded in the manifest, in the order they were created.
+    pub fn links(&self) -> Result<Vec<Link>, CorpusError> {
+        let mut links = Vec::new();
+        self.update_manifest(|paths| {
+            links = paths.iter().map(|path| self.link_at(path)).collect();
+        })?;
+        Ok(links)
+    }
+
+    /// Removes a recorded `link`, unless it no longer points at its entry,
+    /// and drops it from the manifest.
+    ///
+    /// Returns whether the symlink was removed.
+    pub fn unlink(&self, link: &Link) -> Result<bool, CorpusError> {
+        let is_linked = link.is_linked();
+        if is_linked {
+            remove_symlink(&link.path)?;
+        }
+        self.update_manifest(|paths| paths.retain(|path| *path != link.path))?;
+        Ok(is_linked)
+    }
+
+    /// Drops the links which no longer point at their entries (e.g. since
+    /// their source directory was removed) from the manifest, and returns
+    /// them.
+    pub fn prune_links(&self) -> Result<Vec<Link>, CorpusError> {
+        let mut pruned = Vec::new();
+        self.update_manifest(|paths| {
+            paths.retain(|path| {
+                let link = self.link_at(path);
+                let is_linked = link.is_linked();
+                if !is_linked {
+                    pruned.push(link);
+                }
+                is_linked
+            })
+        })?;
+        Ok(pruned)
+    }
+
+    /// Follows the move of the entry for `from_source` (the `from` entry, and
+    /// with it any entries within its directory) to `to_source`: the links to
+    /// the moved entries are moved to the corresponding paths within
+    /// `to_source`, and pointed at the moved entries.
+    ///
+    /// A link which moved along with its source directory is repointed, and
+    /// one which didn't is recreated, if nothing else is in its place. The
+    /// links which couldn't be are dropped from the manifest.
+    pub(crate) fn move_links(
+        &self,
+        from_source: &Path,
+        to_source: &Path,
+        from: &Path,
+    ) -> Result<(), CorpusError> {
+        let mut moved = Vec::new();
+        for link in self.links()? {
+            let relative = match link.path.strip_prefix(from_source) {
+                Ok(relative) if link.entry.starts_with(from) => relative,
+                _ => continue,
+            };
+            if link.is_linked() {
+                remove_symlink(&link.path)?;
+            }
+
+            let new = self.link_at(&to_source.join(relative));
+            if matches!(std::fs::read_link(&new.path), Ok(target) if target == link.entry) {
+                remove_symlink(&new.path)?;
+            }
+            let is_free = std::fs::symlink_metadata(&new.path).is_err();
+            if is_free && matches!(new.path.parent(), Some(parent) if parent.is_dir()) {
+                symlink(&new.entry, &new.path)?;
+            }
+            moved.push((link.path, new));
+        }
+
+        self.update_manifest(|paths| {
+            for (old, new) in &moved {
+                paths.retain(|path| path != old && *path != new.path);
+                if new.is_linked() {
+                    paths.push(new.path.clone());
+                }
+            }
+        })
+    }
+
+    fn link_at(&self, path: &Path) -> Link {
+        Link {
+            path: path.to_path_buf(),
+            entry: self.path(path.parent()),
+        }
+    }
+
+    /// Applies `update` to the links in the manifest while holding a lock on
+    /// it, and writes them back if they changed.
+    fn update_manifest<F: FnMut(&mut Vec<PathBuf>)>(
+        &self,
+        mut update: F,
+    ) -> Result<(), CorpusError> {
+        let path = self.root_location.join(MANIFEST);
+        if !path.exists() {
+            let mut paths = Vec::new();
+            update(&mut paths);
+            if paths.is_empty() {
+                return Ok(());
+            }
+            self.create_dir_all(&self.root_location)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        file.lock()?;
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        let mut paths = parse(&contents);
+        let original = paths.clone();
+        update(&mut paths);
+
+        if paths != original {
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&serialize(&paths))?;
+            file.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if `path` is the manifest written by [`Corpus::link`].
+pub(crate) fn is_manifest(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == MANIFEST)
+}
+
+/// The manifest holds NUL-terminated paths, which can't contain NULs.
+fn parse(contents: &[u8]) -> Vec<PathBuf> {
+    contents
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+fn serialize(paths: &[PathBuf]) -> Vec<u8> {
+    let mut contents = Vec::new();
+    for path in paths {
+        contents.extend(path_to_bytes(path));
+        contents.push(0);
+    }
+    contents
+}
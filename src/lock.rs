@@ -0,0 +1,169 @@
+use crate::overflow;
+use crate::path::MaybePath;
+use crate::{Corpus, CorpusError};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+/// The extension of the lock file guarding an entry.
+const LOCK_EXTENSION: &str = "lock";
+
+/// How an entry lock is held.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockMode {
+    /// Any number of shared locks may be held at once, but never alongside
+    /// an exclusive lock. Suitable for readers.
+    Shared,
+
+    /// Only a single exclusive lock may be held at once. Suitable for writers.
+    Exclusive,
+}
+
+/// An advisory lock on a "corpus" entry, which is released when dropped.
+///
+/// The lock is held (via `flock` on unix) on a `<name>.lock` file next to the
+/// entry rather than on the entry itself, so that the entry can still be
+/// atomically replaced (see [`Corpus::write_atomic`]) or be a directory. Lock
+/// files are never yielded as entries, and persist until
+/// [`Corpus::remove_locks`] removes them.
+#[derive(Debug)]
+pub struct EntryLock {
+    file: File,
+    path: PathBuf,
+    mode: LockMode,
+}
+
+impl EntryLock {
+    /// The path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The mode the lock is held in.
+    pub fn mode(&self) -> LockMode {
+        self.mode
+    }
+}
+
+impl Drop for EntryLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl Corpus {
+    /// Locks the entry for the `input` source path, blocking until the lock
+    /// is available.
+    ///
+    /// Locks are advisory: they only exclude other processes which also lock
+    /// the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use corpus::{builder, LockMode};
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to("/home")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let lock = corpus.lock("/home/foo", LockMode::Exclusive).unwrap();
+    /// assert!(corpus.try_lock("/home/foo", LockMode::Shared).unwrap().is_none());
+    ///
+    /// drop(lock);
+    /// assert!(corpus.try_lock("/home/foo", LockMode::Shared).unwrap().is_some());
+    /// ```
+    pub fn lock<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+        mode: LockMode,
+    ) -> Result<EntryLock, CorpusError> {
+        let (file, path) = self.open_lock_file(input)?;
+        match mode {
+            LockMode::Shared => file.lock_shared()?,
+            LockMode::Exclusive => file.lock()?,
+        }
+        Ok(EntryLock { file, path, mode })
+    }
+
+    /// Locks the entry for the `input` source path, returning `None` rather
+    /// than blocking if a conflicting lock is held.
+    pub fn try_lock<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+        mode: LockMode,
+    ) -> Result<Option<EntryLock>, CorpusError> {
+        let (file, path) = self.open_lock_file(input)?;
+        let result = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock(),
+        };
+        match result {
+            Ok(()) => Ok(Some(EntryLock { file, path, mode })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(error)) => Err(error.into()),
+        }
+    }
+
+    /// Removes the lock file for the entry at the "corpus" `path`, e.g. once
+    /// the entry has been removed. The locks of entries nested within an
+    /// entry's directory are removed along with the directory.
+    ///
+    /// Any process still waiting on the lock is no longer excluded from a
+    /// process locking the entry afresh.
+    pub fn remove_locks(&self, path: &Path) -> Result<(), CorpusError> {
+        match std::fs::remove_file(self.lock_path(path)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn open_lock_file<'a, I: Into<MaybePath<'a>>>(
+        &self,
+        input: I,
+    ) -> Result<(File, PathBuf), CorpusError> {
+        let path = self.lock_path(&self.prepare_path(input)?);
+        self.create_parents(&path)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        Ok((file, path))
+    }
+
+    /// Returns the lock file guarding the entry at the "corpus" `path`.
+    ///
+    /// This is `<entry>.lock`, next to the entry. The root entry (which may
+    /// lie outside of the root, e.g. `<root>.toml`) is locked by the root's
+    /// own `.lock`, and any other path (for a source outside of every
+    /// "relative" directory) by a hash of the path within the root.
+    fn lock_path(&self, path: &Path) -> PathBuf {
+        if self.bases().any(|(base, _)| self.map_source(base) == path) {
+            return self.root_location.join(format!(".{}", LOCK_EXTENSION));
+        }
+        if !path.starts_with(&self.root_location) {
+            return self.root_location.join(format!(
+                ".{}.{}",
+                overflow::marker(path),
+                LOCK_EXTENSION
+            ));
+        }
+
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}", LOCK_EXTENSION));
+        path.with_file_name(file_name)
+    }
+}
+
+/// Returns `true` if `path` is a lock file written by [`Corpus::lock`]: an
+/// empty file whose name ends in `.lock`.
+pub(crate) fn is_lock_file(path: &Path) -> bool {
+    let suffix = format!(".{}", LOCK_EXTENSION);
+    matches!(path.file_name().and_then(|name| name.to_str()), Some(name) if name.ends_with(&suffix))
+        && matches!(std::fs::metadata(path), Ok(metadata) if metadata.is_file() && metadata.len() == 0)
+}
@@ -0,0 +1,152 @@
+use crate::entry::Entry;
+use crate::path::MaybePath;
+use crate::Corpus;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+type Predicate<'c> = Box<dyn Fn(&Path) -> bool + 'c>;
+
+enum Stop {
+    Marker(OsString),
+    Boundary(PathBuf),
+}
+
+/// Configures a search for the nearest existing ancestor entry.
+///
+/// Produced by [`Corpus::nearest`]. By default this behaves like
+/// [`Corpus::find_nearest`]: the first ancestor entry which exists is the
+/// match. Additional predicates narrow what counts as a match, and the
+/// depth limit and stop conditions bound how far upwards the search goes.
+///
+/// # Examples
+///
+/// ```
+/// use corpus::builder;
+///
+/// let corpus = builder()
+///     .with_root("/home/.config")
+///     .relative_to("/home")
+///     .with_name("foo")
+///     .build()
+///     .unwrap();
+///
+/// let result = corpus
+///     .nearest()
+///     .is_dir()
+///     .max_depth(2)
+///     .stop_at_marker(".git")
+///     .find("/home/bar/baz");
+///
+/// assert_eq!(result, None);
+/// ```
+pub struct NearestQuery<'c> {
+    corpus: &'c Corpus,
+    predicates: Vec<Predicate<'c>>,
+    max_depth: Option<usize>,
+    stops: Vec<Stop>,
+}
+
+impl<'c> NearestQuery<'c> {
+    pub(crate) fn new(corpus: &'c Corpus) -> Self {
+        Self {
+            corpus,
+            predicates: Vec::new(),
+            max_depth: None,
+            stops: Vec::new(),
+        }
+    }
+
+    /// Only match entries for which `predicate` returns `true`.
+    ///
+    /// The predicate receives the "corpus" path of a candidate which is
+    /// already known to exist. Multiple predicates must all match.
+    pub fn matching<F: Fn(&Path) -> bool + 'c>(mut self, predicate: F) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// Only match entries which are files.
+    pub fn is_file(self) -> Self {
+        self.matching(|path| path.is_file())
+    }
+
+    /// Only match entries which are directories.
+    pub fn is_dir(self) -> Self {
+        self.matching(|path| path.is_dir())
+    }
+
+    /// Only match directory entries which contain a child named `name`.
+    pub fn contains<S: Into<OsString>>(self, name: S) -> Self {
+        let name = name.into();
+        self.matching(move |path| path.join(&name).exists())
+    }
+
+    /// Only match entries which are non-empty files or directories.
+    pub fn non_empty(self) -> Self {
+        self.matching(|path| {
+            if path.is_dir() {
+                std::fs::read_dir(path).is_ok_and(|mut dir| dir.next().is_some())
+            } else {
+                std::fs::metadata(path).is_ok_and(|meta| meta.len() > 0)
+            }
+        })
+    }
+
+    /// Limits the search to `depth` parents above the input path.
+    ///
+    /// A depth of `0` only considers the entry for the input path itself.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Stops the search at the first source directory containing `marker`.
+    ///
+    /// The directory containing the marker is still considered, but none of
+    /// its parents are. For example `stop_at_marker(".git")` confines the
+    /// search to the enclosing repository.
+    pub fn stop_at_marker<S: Into<OsString>>(mut self, marker: S) -> Self {
+        self.stops.push(Stop::Marker(marker.into()));
+        self
+    }
+
+    /// Stops the search at the `boundary` source directory.
+    ///
+    /// The boundary itself is still considered, but none of its parents are.
+    pub fn stop_at<P: Into<PathBuf>>(mut self, boundary: P) -> Self {
+        let boundary = self.corpus.source(boundary.into().as_path());
+        self.stops.push(Stop::Boundary(boundary));
+        self
+    }
+
+    /// Runs the search upwards from `input`, returning the matching entry.
+    pub fn find<'a, I: Into<MaybePath<'a>>>(&self, input: I) -> Option<Entry> {
+        for entry in self.corpus.ancestors(input) {
+            if let Some(max_depth) = self.max_depth {
+                if entry.depth > max_depth {
+                    break;
+                }
+            }
+
+            if self.is_match(&entry.path) {
+                return Some(entry);
+            }
+
+            if self.is_stop(&entry.source_path) {
+                break;
+            }
+        }
+        None
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        path.exists() && self.predicates.iter().all(|predicate| predicate(path))
+    }
+
+    fn is_stop(&self, source_path: &Path) -> bool {
+        self.stops.iter().any(|stop| match stop {
+            Stop::Marker(marker) => source_path.join(marker).exists(),
+            Stop::Boundary(boundary) => source_path == boundary,
+        })
+    }
+}
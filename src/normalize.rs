@@ -0,0 +1,131 @@
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+
+/// The Unicode normalization forms which source components can be put into.
+///
+/// Requires the "unicode" feature.
+#[cfg(feature = "unicode")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum UnicodeForm {
+    /// Canonical composition (e.g. what Linux tools typically produce).
+    Nfc,
+
+    /// Canonical decomposition (e.g. what macOS filesystems typically produce).
+    Nfd,
+}
+
+/// How source path components are normalized before being mapped.
+///
+/// Without any normalization, paths which differ only in their Unicode
+/// normalization or letter case map to distinct entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Normalization {
+    /// The Unicode normalization form applied to each component, if any.
+    #[cfg(feature = "unicode")]
+    pub unicode: Option<UnicodeForm>,
+
+    /// Whether each component is lower-cased.
+    pub fold_case: bool,
+}
+
+impl Normalization {
+    /// Returns `true` if this normalization leaves every path unchanged.
+    pub fn is_identity(&self) -> bool {
+        #[cfg(feature = "unicode")]
+        if self.unicode.is_some() {
+            return false;
+        }
+        !self.fold_case
+    }
+
+    /// Normalizes each of the normal components of `path`.
+    pub fn path(&self, path: &Path) -> PathBuf {
+        if self.is_identity() {
+            return path.to_path_buf();
+        }
+
+        path.components()
+            .map(|component| match component {
+                Component::Normal(name) => self.component(name),
+                other => other.as_os_str().to_os_string(),
+            })
+            .collect()
+    }
+
+    /// Normalizes a single path component.
+    ///
+    /// Components which are not valid UTF-8 are returned unchanged.
+    pub fn component(&self, name: &OsStr) -> OsString {
+        let name = match name.to_str() {
+            Some(name) => name,
+            None => return name.to_os_string(),
+        };
+
+        #[cfg(feature = "unicode")]
+        let name: String = {
+            use unicode_normalization::UnicodeNormalization;
+
+            match self.unicode {
+                Some(UnicodeForm::Nfc) => name.nfc().collect(),
+                Some(UnicodeForm::Nfd) => name.nfd().collect(),
+                None => name.to_string(),
+            }
+        };
+
+        if self.fold_case {
+            name.to_lowercase().into()
+        } else {
+            name.into()
+        }
+    }
+
+    /// Recovers the original spelling of a source `path` which was mapped
+    /// back from a normalized entry.
+    ///
+    /// Normalization is lossy (e.g. `~/Foo` and `~/foo` share an entry), so
+    /// each component which doesn't exist as written is looked up among its
+    /// directory's children, and replaced by the one child normalizing to
+    /// it. Components without exactly one such child, and every component
+    /// after them, are left as they are.
+    pub(crate) fn restore(&self, path: &Path) -> PathBuf {
+        if self.is_identity() {
+            return path.to_path_buf();
+        }
+
+        let mut restored = PathBuf::new();
+        let mut components = path.components();
+        for component in components.by_ref() {
+            let name = match component {
+                Component::Normal(name) => name,
+                other => {
+                    restored.push(other);
+                    continue;
+                }
+            };
+            if restored.join(name).symlink_metadata().is_ok() {
+                restored.push(name);
+                continue;
+            }
+
+            let mut matches = match std::fs::read_dir(&restored) {
+                Ok(children) => children
+                    .filter_map(|child| child.ok())
+                    .map(|child| child.file_name())
+                    .filter(|child| self.component(child) == name)
+                    .collect::<Vec<_>>(),
+                Err(_) => Vec::new(),
+            };
+            if matches.len() != 1 {
+                restored.push(name);
+                break;
+            }
+            restored.push(matches.remove(0));
+        }
+        restored.extend(components);
+        restored
+    }
+}
@@ -0,0 +1,169 @@
+use crate::fs::write_atomic;
+use crate::path::{join, path_from_bytes, path_to_bytes};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+
+/// The prefix of the component which replaces the tail of an overflowing path.
+const MARKER: &str = "~";
+
+/// The extension of the sidecar file recording an overflowing source path.
+const SIDECAR_EXTENSION: &str = "source";
+
+/// The length limits which generated "corpus" paths must fit within.
+///
+/// Lengths are measured in bytes of the platform's path representation. The
+/// defaults correspond to the common Linux values of `PATH_MAX` and
+/// `NAME_MAX`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PathLimits {
+    /// The maximum length of a whole path.
+    pub max_path: usize,
+
+    /// The maximum length of a single path component.
+    pub max_name: usize,
+}
+
+impl Default for PathLimits {
+    fn default() -> Self {
+        Self {
+            max_path: 4096,
+            max_name: 255,
+        }
+    }
+}
+
+impl PathLimits {
+    /// Returns `true` if `path` fits within both limits.
+    pub fn fits(&self, path: &Path) -> bool {
+        path.as_os_str().len() <= self.max_path
+            && path
+                .components()
+                .all(|component| component.as_os_str().len() <= self.max_name)
+    }
+
+    /// Deterministically shortens a "corpus" path which does not fit.
+    ///
+    /// Leading components of `relative_path` are kept for as long as they fit,
+    /// and the remaining tail is replaced by a single `~<hash>` component,
+    /// where the hash is taken over the whole of `relative_path`.
+    pub(crate) fn shorten(
+        &self,
+        root: &Path,
+        relative_path: &Path,
+        extension: Option<&str>,
+    ) -> PathBuf {
+        let marker = marker(relative_path);
+        let marker_len = marker.len() + extension.map_or(0, |ext| ext.len() + 1) + 1;
+
+        let mut shortened = root.to_path_buf();
+        for component in relative_path.components() {
+            let name = component.as_os_str();
+            if name.len() > self.max_name
+                || shortened.as_os_str().len() + name.len() + 1 + marker_len > self.max_path
+            {
+                break;
+            }
+            shortened.push(name);
+        }
+
+        shortened.push(marker);
+        if let Some(ext) = extension {
+            shortened.set_extension(ext);
+        }
+        shortened
+    }
+}
+
+/// Returns the `~<hash>` file name standing in for `path`.
+pub(crate) fn marker(path: &Path) -> String {
+    format!("{}{:016x}", MARKER, fnv1a(path))
+}
+
+/// Returns the sidecar path recording the source path of a shortened `path`.
+///
+/// The sidecar is hidden, so that its name never clashes with the entry's
+/// own (e.g. where the corpus extension is itself `source`).
+pub(crate) fn sidecar(path: &Path) -> Option<PathBuf> {
+    let marker = marker_name(path.file_name()?)?;
+    Some(path.with_file_name(format!(".{}.{}", marker, SIDECAR_EXTENSION)))
+}
+
+/// Returns `true` if `path` is a sidecar written for a shortened entry.
+pub(crate) fn is_sidecar(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new(SIDECAR_EXTENSION))
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix('.'))
+            .and_then(|stem| marker_name(OsStr::new(stem)))
+            .is_some()
+}
+
+/// Records `source` in the sidecar of the shortened `path`, whose parent
+/// directory must already exist.
+pub(crate) fn write_sidecar(path: &Path, source: &Path) -> std::io::Result<()> {
+    let sidecar = match sidecar(path) {
+        Some(sidecar) => sidecar,
+        None => return Ok(()),
+    };
+    if read_source(&sidecar).as_deref() == Some(source) {
+        return Ok(());
+    }
+    write_atomic(&sidecar, &path_to_bytes(source))
+}
+
+/// Resolves the source path of a "corpus" `path` (relative to the root) which
+/// passes through a shortened component, using its sidecar.
+pub(crate) fn read_sidecar(root: &Path, relative_path: &Path) -> Option<PathBuf> {
+    let mut shortened = root.to_path_buf();
+    let mut components = relative_path.components();
+    for component in components.by_ref() {
+        shortened.push(component);
+        if let Component::Normal(name) = component {
+            if marker_name(name).is_some() {
+                let source = read_source(&sidecar(&shortened)?)?;
+                return Some(join(&source, components.as_path()));
+            }
+        }
+    }
+    None
+}
+
+/// Returns `true` if a "corpus" `path` (relative to the root) passes through
+/// a shortened component.
+pub(crate) fn is_shortened(relative_path: &Path) -> bool {
+    relative_path.components().any(|component| match component {
+        Component::Normal(name) => marker_name(name).is_some(),
+        _ => false,
+    })
+}
+
+fn read_source(sidecar: &Path) -> Option<PathBuf> {
+    std::fs::read(sidecar)
+        .ok()
+        .map(|bytes| path_from_bytes(&bytes))
+}
+
+/// Returns the marker portion (sans extension) of a shortened component.
+fn marker_name(name: &OsStr) -> Option<&str> {
+    let name = name.to_str()?;
+    let marker = name.split('.').next()?;
+    let hash = marker.strip_prefix(MARKER)?;
+    if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(marker)
+    } else {
+        None
+    }
+}
+
+/// A 64-bit FNV-1a hash, which (unlike `DefaultHasher`) is stable across
+/// releases and platforms.
+fn fnv1a(path: &Path) -> u64 {
+    path_to_bytes(path)
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
@@ -1,4 +1,5 @@
-use std::path::{Path, PathBuf};
+use crate::CorpusError;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, PartialEq)]
 pub enum MaybePath<'a> {
@@ -40,11 +41,67 @@ impl<'a> MaybePath<'a> {
     }
 }
 
+/// Joins `remainder` onto `base`, without a trailing separator when it is empty.
+pub(crate) fn join(base: &Path, remainder: &Path) -> PathBuf {
+    if remainder.as_os_str().is_empty() {
+        base.to_path_buf()
+    } else {
+        base.join(remainder)
+    }
+}
+
+/// Drops any root or prefix from `path`, so that it joins onto another path.
+pub(crate) fn relative(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::RootDir | Component::Prefix(_)))
+        .collect()
+}
+
+/// Checks that `name` is a single plain file name (e.g. `.venv`), which can't
+/// escape the directory it is joined onto.
+pub(crate) fn file_name(name: &Path) -> Result<&Path, CorpusError> {
+    let mut components = name.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(name),
+        _ => Err(CorpusError::InvalidName(name.to_path_buf())),
+    }
+}
+
+/// The raw bytes of `path`, for storing it in files or writing it to a
+/// stream.
+///
+/// On unix these are the exact OS bytes. Elsewhere the path is converted
+/// (lossily) to UTF-8.
+pub fn path_to_bytes(path: &Path) -> Vec<u8> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes().to_vec()
+    }
+    #[cfg(not(unix))]
+    {
+        path.to_string_lossy().as_bytes().to_vec()
+    }
+}
+
+/// The path whose bytes (as produced by [`path_to_bytes`]) are `bytes`.
+pub fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        std::ffi::OsStr::from_bytes(bytes).into()
+    }
+    #[cfg(not(unix))]
+    {
+        String::from_utf8_lossy(bytes).into_owned().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
 
-    use super::MaybePath;
+    use super::{file_name, path_from_bytes, path_to_bytes, MaybePath};
 
     #[test]
     fn test_from_str() {
@@ -69,4 +126,18 @@ mod tests {
         let path = MaybePath::from(None);
         assert_eq!(path, MaybePath::CurrentDir);
     }
+
+    #[test]
+    fn test_file_name() {
+        assert!(file_name(Path::new(".venv")).is_ok());
+        for name in ["", ".", "..", "../x", "a/b", "/x"].iter() {
+            assert!(file_name(Path::new(name)).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_path_bytes() {
+        let path = Path::new("/foo/bär");
+        assert_eq!(path_from_bytes(&path_to_bytes(path)), path);
+    }
 }
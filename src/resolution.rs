@@ -0,0 +1,83 @@
+use path_absolutize::Absolutize;
+use std::path::{Path, PathBuf};
+
+/// The options for how source paths are resolved before being mapped.
+///
+/// The choice matters whenever a directory is reachable through a symlink:
+/// with `~/src -> /data/src`, the lexical paths `~/src/foo` and
+/// `/data/src/foo` would otherwise produce two different entries for the
+/// same project.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Resolution {
+    /// Purely lexical: `.` and `..` are normalized, but symlinks are kept.
+    #[default]
+    Lexical,
+
+    /// Symlinks are resolved (as with `realpath`). Trailing components which
+    /// do not exist yet are appended lexically to the deepest existing parent.
+    Canonical,
+
+    /// The current directory is taken from `$PWD` (as maintained by the shell)
+    /// whenever it refers to the actual current directory, preserving the
+    /// symlinked path the user navigated through. Otherwise lexical.
+    PreferLogical,
+}
+
+impl Resolution {
+    /// Resolves `path` into an absolute path, according to the resolution mode.
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        match self {
+            Self::Lexical => lexical(path),
+            Self::Canonical => canonical(&lexical(path)),
+            Self::PreferLogical if path.is_relative() => lexical(&self.current_dir().join(path)),
+            Self::PreferLogical => lexical(path),
+        }
+    }
+
+    /// Returns the current directory, according to the resolution mode.
+    pub fn current_dir(&self) -> PathBuf {
+        let current_dir = std::env::current_dir().unwrap_or_else(|_| Path::new(".").to_path_buf());
+        match self {
+            Self::Lexical => current_dir,
+            Self::Canonical => canonical(&current_dir),
+            Self::PreferLogical => logical_current_dir().unwrap_or(current_dir),
+        }
+    }
+}
+
+fn lexical(path: &Path) -> PathBuf {
+    path.absolutize()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            let remainder = path
+                .strip_prefix(ancestor)
+                .unwrap_or_else(|_| Path::new(""));
+            if remainder.as_os_str().is_empty() {
+                return canonical;
+            }
+            return canonical.join(remainder);
+        }
+    }
+    path.to_path_buf()
+}
+
+fn logical_current_dir() -> Option<PathBuf> {
+    let pwd = PathBuf::from(std::env::var_os("PWD")?);
+    if !pwd.is_absolute() {
+        return None;
+    }
+
+    let physical = std::env::current_dir().ok()?.canonicalize().ok()?;
+    if pwd.canonicalize().ok()? == physical {
+        Some(lexical(&pwd))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,149 @@
+use crate::normalize::Normalization;
+use crate::path::{join, relative};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+enum Pattern {
+    Prefix(PathBuf),
+    Glob(PathBuf),
+}
+
+/// A rule which maps a family of source paths onto a subpath of the root.
+///
+/// Rewrites let several source trees share a corpus namespace. For example,
+/// with rules mapping both `/home/me/work` and `/mnt/work` to `work`, the
+/// source paths `/home/me/work/foo` and `/mnt/work/foo` share the entry
+/// `<root>/work/foo`.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use corpus::{builder, Rewrite};
+///
+/// let corpus = builder()
+///     .with_root("/config")
+///     .relative_to("/home/me")
+///     .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+///     .with_rewrite(Rewrite::glob("/mnt/*/work", "work"))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(corpus.path("/mnt/disk/work/foo"), PathBuf::from("/config/work/foo"));
+/// assert_eq!(
+///     corpus.get_source_path("/config/work/foo"),
+///     Some(PathBuf::from("/home/me/work/foo"))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rewrite {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pattern: Pattern,
+    target: PathBuf,
+}
+
+impl Rewrite {
+    /// Maps source paths below `prefix` to the same paths below `target`.
+    ///
+    /// `target` is a path relative to the root.
+    pub fn prefix<P: Into<PathBuf>, T: AsRef<Path>>(prefix: P, target: T) -> Self {
+        Self {
+            pattern: Pattern::Prefix(prefix.into()),
+            target: relative(target.as_ref()),
+        }
+    }
+
+    /// Maps source paths whose leading components match `glob` to `target`.
+    ///
+    /// The glob is matched component by component, where `*` matches any
+    /// run of characters and `?` matches any single character within a
+    /// component. Since the matched values can not be recovered, glob rules
+    /// are never used by [`crate::Corpus::get_source_path`].
+    pub fn glob<G: Into<PathBuf>, T: AsRef<Path>>(glob: G, target: T) -> Self {
+        Self {
+            pattern: Pattern::Glob(glob.into()),
+            target: relative(target.as_ref()),
+        }
+    }
+
+    /// Resolves a prefix pattern with the given `resolve` function.
+    pub(crate) fn resolved<F: Fn(&Path) -> PathBuf>(self, resolve: F) -> Self {
+        match self.pattern {
+            Pattern::Prefix(prefix) => Self {
+                pattern: Pattern::Prefix(resolve(&prefix)),
+                target: self.target,
+            },
+            Pattern::Glob(_) => self,
+        }
+    }
+
+    /// Normalizes the target, so that it matches the normalized entries
+    /// below it.
+    pub(crate) fn normalized(self, normalization: &Normalization) -> Self {
+        Self {
+            target: normalization.path(&self.target),
+            ..self
+        }
+    }
+
+    /// Returns the path relative to the root which `source` maps to, if the
+    /// rule applies to it.
+    pub(crate) fn apply(&self, source: &Path) -> Option<PathBuf> {
+        Some(join(&self.target, self.remainder(source)?))
+    }
+
+    /// Returns the leading part of `source` which the rule matched, if it
+    /// applies to it (e.g. `/mnt/disk/work` for the glob `/mnt/*/work`).
+    pub(crate) fn matched(&self, source: &Path) -> Option<PathBuf> {
+        let remainder = self.remainder(source)?.components().count();
+        let components = source.components();
+        let matched = components.clone().count() - remainder;
+        Some(components.take(matched).collect())
+    }
+
+    /// Returns the rest of `source` below the part the rule matched.
+    fn remainder<'a>(&self, source: &'a Path) -> Option<&'a Path> {
+        let remainder = match &self.pattern {
+            Pattern::Prefix(prefix) => source.strip_prefix(prefix).ok()?,
+            Pattern::Glob(glob) => {
+                let mut components = source.components();
+                for pattern in glob.components() {
+                    let component = components.next()?;
+                    let name = component.as_os_str().to_string_lossy();
+                    let pattern: Vec<char> =
+                        pattern.as_os_str().to_string_lossy().chars().collect();
+                    let name: Vec<char> = name.chars().collect();
+                    if !wildcard_match(&pattern, &name) {
+                        return None;
+                    }
+                }
+                components.as_path()
+            }
+        };
+        Some(remainder)
+    }
+
+    /// Returns the source path for a `relative_path` (relative to the root),
+    /// if this rule is invertible and produced it.
+    pub(crate) fn invert(&self, relative_path: &Path) -> Option<PathBuf> {
+        match &self.pattern {
+            Pattern::Prefix(prefix) => {
+                let remainder = relative_path.strip_prefix(&self.target).ok()?;
+                Some(join(prefix, remainder))
+            }
+            Pattern::Glob(_) => None,
+        }
+    }
+}
+
+fn wildcard_match(pattern: &[char], value: &[char]) -> bool {
+    match pattern.split_first() {
+        None => value.is_empty(),
+        Some(('*', rest)) => (0..=value.len()).any(|skip| wildcard_match(rest, &value[skip..])),
+        Some(('?', rest)) => !value.is_empty() && wildcard_match(rest, &value[1..]),
+        Some((c, rest)) => value.first() == Some(c) && wildcard_match(rest, &value[1..]),
+    }
+}
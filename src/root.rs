@@ -40,7 +40,7 @@ impl RootLocation {
                 };
                 Ok(path)
             }
-            Self::Raw(path) => Ok(path.to_path_buf()),
+            Self::Raw(path) => Ok::<_, CorpusError>(path.to_path_buf()),
         }?;
 
         Ok(path
@@ -85,6 +85,45 @@ impl From<String> for RootLocation {
     }
 }
 
+/// Serializes to the same string syntax accepted by `From<&str>`.
+///
+/// Raw paths which would otherwise be read back as one of the named
+/// locations (e.g. a relative directory named "xdg-data") are prefixed with
+/// `./` to keep them distinct.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RootLocation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            #[cfg(feature = "xdg")]
+            Self::XDGData => serializer.serialize_str("xdg-data"),
+
+            #[cfg(feature = "xdg")]
+            Self::XDGConfig => serializer.serialize_str("xdg-config"),
+
+            #[cfg(feature = "xdg")]
+            Self::XDGCache => serializer.serialize_str("xdg-cache"),
+
+            Self::Raw(path) => {
+                let raw = path
+                    .to_str()
+                    .ok_or_else(|| serde::ser::Error::custom("root path must be valid UTF-8"))?;
+                if Self::from(raw) == *self {
+                    serializer.serialize_str(raw)
+                } else {
+                    serializer.serialize_str(&format!("./{}", raw))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RootLocation {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::RootLocation;
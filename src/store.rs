@@ -0,0 +1,134 @@
+use crate::path::MaybePath;
+use crate::{Corpus, CorpusError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// The serialization formats which entries can be loaded from and stored as,
+/// keyed by the corpus extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    #[cfg(feature = "toml")]
+    Toml,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl Format {
+    fn from_extension(extension: Option<&str>) -> Result<Self, CorpusError> {
+        Ok(match extension {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::Toml,
+            #[cfg(feature = "json")]
+            Some("json") => Self::Json,
+            #[cfg(feature = "yaml")]
+            Some("yaml") | Some("yml") => Self::Yaml,
+            #[cfg(feature = "ron")]
+            Some("ron") => Self::Ron,
+            _ => {
+                return Err(CorpusError::UnsupportedFormat(
+                    extension.map(str::to_string),
+                ))
+            }
+        })
+    }
+
+    fn deserialize<T: DeserializeOwned>(self, contents: &str) -> Result<T, String> {
+        match self {
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<String, String> {
+        match self {
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Typed access to entries, for tools which use the corpus as a
+/// per-directory settings store.
+///
+/// The format is chosen by the corpus extension: `toml`, `json`, `yaml` (or
+/// `yml`) and `ron` are supported, each behind the feature of the same name.
+impl Corpus {
+    /// Loads and deserializes the entry for the `input` source path.
+    ///
+    /// Returns `Ok(None)` if the entry does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "toml")]
+    /// # {
+    /// use std::collections::BTreeMap;
+    /// use corpus::builder;
+    ///
+    /// let root = tempfile::tempdir().unwrap();
+    /// let corpus = builder()
+    ///     .with_root(root.path())
+    ///     .relative_to("/home")
+    ///     .with_extension("toml")
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut settings = BTreeMap::new();
+    /// settings.insert("key".to_string(), 1);
+    /// corpus.store("/home/foo", &settings).unwrap();
+    ///
+    /// assert_eq!(corpus.load("/home/foo").unwrap(), Some(settings));
+    /// assert_eq!(corpus.load::<BTreeMap<String, i32>>("/home/bar").unwrap(), None);
+    /// # }
+    /// ```
+    pub fn load<'a, T: DeserializeOwned>(
+        &self,
+        input: impl Into<MaybePath<'a>>,
+    ) -> Result<Option<T>, CorpusError> {
+        let format = Format::from_extension(self.extension.as_deref())?;
+        let path = self.path(input);
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        format
+            .deserialize(&contents)
+            .map(Some)
+            .map_err(|message| CorpusError::Deserialize { path, message })
+    }
+
+    /// Serializes `value` and atomically stores it as the entry for the
+    /// `input` source path (see [`Corpus::write_atomic`]).
+    ///
+    /// Returns the path of the written entry.
+    pub fn store<'a, T: Serialize + ?Sized>(
+        &self,
+        input: impl Into<MaybePath<'a>>,
+        value: &T,
+    ) -> Result<PathBuf, CorpusError> {
+        let format = Format::from_extension(self.extension.as_deref())?;
+        let contents = format.serialize(value).map_err(CorpusError::Serialize)?;
+        self.write_atomic(input, contents)
+    }
+}
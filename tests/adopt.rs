@@ -0,0 +1,144 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use corpus::{builder, CorpusError, LockMode};
+
+#[test]
+fn test_adopt_and_unadopt() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let project = source.path().join("project");
+    fs::create_dir_all(project.join(".venv")).unwrap();
+    fs::write(project.join(".venv/pyvenv.cfg"), "home").unwrap();
+
+    let entry = corpus.adopt(project.as_path(), ".venv", true).unwrap();
+    assert_eq!(entry, root.path().join("project"));
+    assert_eq!(
+        fs::read_to_string(entry.join("pyvenv.cfg")).unwrap(),
+        "home"
+    );
+    assert_eq!(fs::read_link(project.join(".venv")).unwrap(), entry);
+
+    let local = corpus.unadopt(project.as_path(), ".venv").unwrap();
+    assert_eq!(local, project.join(".venv"));
+    assert!(!entry.exists());
+    assert_eq!(
+        fs::read_to_string(local.join("pyvenv.cfg")).unwrap(),
+        "home"
+    );
+}
+
+#[test]
+fn test_adopt_never_overwrites() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let project = source.path().join("project");
+    fs::create_dir_all(project.join(".venv")).unwrap();
+    fs::create_dir_all(root.path().join("project")).unwrap();
+
+    let error = corpus.adopt(project.as_path(), ".venv", false).unwrap_err();
+    assert!(matches!(error, CorpusError::Move { .. }));
+    assert!(project.join(".venv").is_dir());
+
+    let error = corpus.unadopt(project.as_path(), ".venv").unwrap_err();
+    assert!(matches!(error, CorpusError::Move { .. }));
+}
+
+#[test]
+fn test_names_stay_in_the_source_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let project = source.path().join("project");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(source.path().join("outside"), "").unwrap();
+
+    let outside = source.path().join("outside");
+    for name in ["../outside", outside.to_str().unwrap(), "sub/.venv", ".."].iter() {
+        let error = corpus.adopt(project.as_path(), name, false).unwrap_err();
+        assert!(matches!(error, CorpusError::InvalidName(_)), "{}", name);
+        let error = corpus.unadopt(project.as_path(), name).unwrap_err();
+        assert!(matches!(error, CorpusError::InvalidName(_)), "{}", name);
+    }
+    assert!(outside.exists());
+}
+
+#[test]
+fn test_move_entry() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let (old, new) = (source.path().join("old"), source.path().join("new"));
+    fs::create_dir_all(&old).unwrap();
+    fs::create_dir_all(root.path().join("old")).unwrap();
+    fs::write(root.path().join("old/data"), "data").unwrap();
+    drop(corpus.lock(old.as_path(), LockMode::Exclusive).unwrap());
+    corpus.link(old.as_path(), ".venv").unwrap().unwrap();
+
+    // The source directory moves first, taking its (now stale) link along.
+    fs::rename(&old, &new).unwrap();
+    let entry = corpus.move_entry(old.as_path(), new.as_path()).unwrap();
+    assert_eq!(entry, root.path().join("new"));
+    assert_eq!(fs::read_to_string(entry.join("data")).unwrap(), "data");
+    assert!(!root.path().join("old").exists());
+    assert!(!root.path().join("old.lock").exists());
+
+    assert_eq!(fs::read_link(new.join(".venv")).unwrap(), entry);
+    let links = corpus.links().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].path, new.join(".venv"));
+    assert!(links[0].is_linked());
+
+    let error = corpus.move_entry(old.as_path(), new.as_path()).unwrap_err();
+    assert!(matches!(error, CorpusError::Move { .. }));
+    fs::create_dir_all(root.path().join("old")).unwrap();
+    let error = corpus.move_entry(old.as_path(), new.as_path()).unwrap_err();
+    assert!(matches!(error, CorpusError::Move { .. }));
+}
+
+#[test]
+fn test_move_entry_relinks_in_place() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let (old, new) = (source.path().join("old"), source.path().join("new"));
+    fs::create_dir_all(&old).unwrap();
+    fs::create_dir_all(&new).unwrap();
+    fs::create_dir_all(root.path().join("old")).unwrap();
+    corpus.link(old.as_path(), ".venv").unwrap().unwrap();
+
+    let entry = corpus.move_entry(old.as_path(), new.as_path()).unwrap();
+    assert!(fs::symlink_metadata(old.join(".venv")).is_err());
+    assert_eq!(fs::read_link(new.join(".venv")).unwrap(), entry);
+    let links = corpus.links().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].path, new.join(".venv"));
+}
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use corpus::{builder, Entry};
+
+#[test]
+fn test_with_extension() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    let result: Vec<Entry> = corpus.ancestors("/home/foo/bar").collect();
+
+    assert_eq!(
+        result,
+        vec![
+            Entry {
+                path: PathBuf::from("/home/.config/foo/bar.toml"),
+                source_path: PathBuf::from("/home/foo/bar"),
+                depth: 0,
+            },
+            Entry {
+                path: PathBuf::from("/home/.config/foo.toml"),
+                source_path: PathBuf::from("/home/foo"),
+                depth: 1,
+            },
+            Entry {
+                path: PathBuf::from("/home/.config.toml"),
+                source_path: PathBuf::from("/home"),
+                depth: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_rev() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    let result: Vec<PathBuf> = corpus
+        .ancestors("/home/foo/bar")
+        .rev()
+        .map(|entry| entry.path)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/home/.config"),
+            PathBuf::from("/home/.config/foo"),
+            PathBuf::from("/home/.config/foo/bar"),
+        ]
+    );
+}
+
+#[test]
+fn test_double_ended_meet_in_middle() {
+    let corpus = builder().with_root("/config").build().unwrap();
+    let mut ancestors = corpus.ancestors("/a/b/c");
+
+    assert_eq!(ancestors.len(), 4);
+    assert_eq!(
+        ancestors.next().unwrap().source_path,
+        PathBuf::from("/a/b/c")
+    );
+    assert_eq!(
+        ancestors.next_back().unwrap().source_path,
+        PathBuf::from("/")
+    );
+    assert_eq!(
+        ancestors.next_back().unwrap().source_path,
+        PathBuf::from("/a")
+    );
+    assert_eq!(ancestors.next().unwrap().source_path, PathBuf::from("/a/b"));
+    assert_eq!(ancestors.next(), None);
+    assert_eq!(ancestors.next_back(), None);
+}
+
+#[test]
+fn test_outside_relative_to() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.ancestors("/root/foo").count(), 0);
+}
+
+#[test]
+fn test_dotted_directories() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    let result: Vec<PathBuf> = corpus
+        .ancestors("/home/proj.v2/src")
+        .map(|entry| entry.path)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/home/.config/proj.v2/src.toml"),
+            PathBuf::from("/home/.config/proj.v2.toml"),
+            PathBuf::from("/home/.config.toml"),
+        ]
+    );
+    assert_ne!(corpus.path("/home/proj"), corpus.path("/home/proj.v2"));
+    assert_eq!(
+        corpus.get_source_path("/home/.config/proj.v2.toml"),
+        Some(PathBuf::from("/home/proj.v2"))
+    );
+}
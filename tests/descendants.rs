@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use corpus::builder;
+
+fn touch(path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, "").unwrap();
+}
+
+#[test]
+fn test_with_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    touch(&root.path().join("foo.toml"));
+    touch(&root.path().join("foo/bar.toml"));
+    touch(&root.path().join("foo/bar/baz.toml"));
+    touch(&root.path().join("foo/other.txt"));
+    touch(&root.path().join("foo/nope/deeper.toml"));
+    touch(&root.path().join("elsewhere.toml"));
+
+    let result: Vec<(PathBuf, usize)> = corpus
+        .descendants("/home/foo")
+        .map(|entry| (entry.source_path, entry.depth))
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            (PathBuf::from("/home/foo/bar"), 1),
+            (PathBuf::from("/home/foo/bar/baz"), 2),
+            (PathBuf::from("/home/foo/nope/deeper"), 2),
+        ]
+    );
+}
+
+#[test]
+fn test_does_not_walk_into_entries() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("git")
+        .build()
+        .unwrap();
+
+    fs::create_dir_all(root.path().join("foo/bar.git/objects")).unwrap();
+    touch(&root.path().join("foo/bar.git/HEAD"));
+
+    let result: Vec<PathBuf> = corpus
+        .descendants("/home")
+        .map(|entry| entry.path)
+        .collect();
+
+    assert_eq!(result, vec![root.path().join("foo/bar.git")]);
+}
+
+#[test]
+fn test_without_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    touch(&root.path().join("foo/bar/baz"));
+
+    // `/home/foo/bar` doesn't exist, so its entry isn't walked.
+    let result: Vec<PathBuf> = corpus
+        .descendants("/home/foo")
+        .map(|entry| entry.source_path)
+        .collect();
+
+    assert_eq!(result, vec![PathBuf::from("/home/foo/bar")]);
+}
+
+#[test]
+fn test_without_extension_keeps_entry_data() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    fs::create_dir_all(source.path().join("proj/sub")).unwrap();
+    fs::create_dir_all(root.path().join("proj/bin")).unwrap();
+    fs::create_dir_all(root.path().join("proj/lib")).unwrap();
+    touch(&root.path().join("proj/pyvenv.cfg"));
+    touch(&root.path().join("proj/sub/pyvenv.cfg"));
+    fs::create_dir_all(root.path().join("gone/bin")).unwrap();
+
+    let result: Vec<PathBuf> = corpus
+        .descendants(source.path())
+        .map(|entry| entry.source_path)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            source.path().join("gone"),
+            source.path().join("proj"),
+            source.path().join("proj/sub"),
+        ]
+    );
+}
+
+#[test]
+fn test_dotted_directory_names() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    touch(&root.path().join("proj.v2/bar.toml"));
+    touch(&root.path().join("proj/other.toml"));
+
+    let result: Vec<PathBuf> = corpus
+        .descendants("/home/proj.v2")
+        .map(|entry| entry.source_path)
+        .collect();
+
+    assert_eq!(result, vec![PathBuf::from("/home/proj.v2/bar")]);
+}
+
+#[test]
+fn test_missing_root() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path().join("missing"))
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.descendants("/foo").count(), 0);
+}
@@ -0,0 +1,150 @@
+use std::fs;
+
+use corpus::{builder, CorpusError, EnsureOptions};
+
+#[test]
+fn test_ensure_is_idempotent() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let path = corpus
+        .ensure("/home/foo/bar", &EnsureOptions::new())
+        .unwrap();
+    assert_eq!(path, root.path().join("foo/bar"));
+    assert!(path.is_dir());
+
+    fs::write(path.join("data"), "kept").unwrap();
+    let options = EnsureOptions::new().with_init("exit 1");
+    assert_eq!(corpus.ensure("/home/foo/bar", &options).unwrap(), path);
+    assert_eq!(fs::read_to_string(path.join("data")).unwrap(), "kept");
+}
+
+#[test]
+fn test_ensure_file() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let path = corpus
+        .ensure("/home/foo", &EnsureOptions::new().as_file())
+        .unwrap();
+    assert!(path.is_file());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ensure_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let path = corpus
+        .ensure("/home/foo", &EnsureOptions::new().with_mode(0o700))
+        .unwrap();
+    let mode = fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+}
+
+#[test]
+fn test_ensure_template() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    let template = tempfile::tempdir().unwrap();
+    fs::create_dir_all(template.path().join("sub")).unwrap();
+    fs::write(template.path().join("sub/file"), "contents").unwrap();
+    fs::write(template.path().join("single"), "single").unwrap();
+
+    let path = corpus
+        .ensure(
+            "/home/foo",
+            &EnsureOptions::new().with_template(template.path()),
+        )
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(path.join("sub/file")).unwrap(),
+        "contents"
+    );
+
+    let options = EnsureOptions::new().with_template(template.path().join("single"));
+    let path = corpus.ensure("/home/bar", &options).unwrap();
+    assert_eq!(fs::read_to_string(path).unwrap(), "single");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ensure_init() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let options = EnsureOptions::new().with_init("touch {}/initialized");
+    let path = corpus.ensure("/home/it's a dir", &options).unwrap();
+    assert!(path.join("initialized").is_file());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ensure_init_quoted_placeholder() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let options = EnsureOptions::new().with_init("touch '{}/single' \"{}/double\" {}/bare");
+    let path = corpus.ensure("/home/it's a \"dir\"", &options).unwrap();
+    assert!(path.join("single").is_file());
+    assert!(path.join("double").is_file());
+    assert!(path.join("bare").is_file());
+    assert!(!root.path().join("\"$1\"").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ensure_failed_init_removes_entry() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+
+    let options = EnsureOptions::new().with_init("touch {}/partial; exit 3");
+    let error = corpus.ensure("/home/foo", &options).unwrap_err();
+    assert!(matches!(error, CorpusError::Init { .. }));
+    assert!(!corpus.path("/home/foo").exists());
+}
+
+#[test]
+fn test_ensure_options_outside_the_corpus() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("foo/bar");
+
+    EnsureOptions::new().as_file().ensure(&path).unwrap();
+    assert!(path.is_file());
+
+    // An existing path is left alone.
+    fs::write(&path, "contents").unwrap();
+    EnsureOptions::new().ensure(&path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "contents");
+}
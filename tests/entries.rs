@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::{builder, Entry};
+
+#[test]
+fn test_with_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_name("sauce")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.entries().count(), 0);
+
+    fs::create_dir_all(root.path().join("sauce/foo")).unwrap();
+    fs::write(root.path().join("sauce.toml"), "").unwrap();
+    fs::write(root.path().join("sauce/foo/bar.toml"), "").unwrap();
+
+    let result: Vec<Entry> = corpus.entries().collect();
+    assert_eq!(
+        result,
+        vec![
+            Entry {
+                path: root.path().join("sauce.toml"),
+                source_path: PathBuf::from("/home"),
+                depth: 0,
+            },
+            Entry {
+                path: root.path().join("sauce/foo/bar.toml"),
+                source_path: PathBuf::from("/home/foo/bar"),
+                depth: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_without_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_name("venv")
+        .build()
+        .unwrap();
+
+    fs::create_dir_all(root.path().join("venv/foo")).unwrap();
+
+    let result: Vec<PathBuf> = corpus.entries().map(|entry| entry.source_path).collect();
+    assert_eq!(
+        result,
+        vec![PathBuf::from("/home"), PathBuf::from("/home/foo")]
+    );
+}
@@ -63,3 +63,48 @@ fn test_with_extension_doesnt_exist() {
 
     assert_eq!(result, None);
 }
+
+#[test]
+fn test_root_entry_with_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_name("sauce")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.find_nearest("/home/foo/bar"), None);
+
+    let root_entry = root.path().join("sauce.toml");
+    std::fs::write(&root_entry, "").unwrap();
+
+    assert_eq!(
+        corpus.find_nearest("/home/foo/bar"),
+        Some(root_entry.clone())
+    );
+    assert_eq!(corpus.find_nearest("/home"), Some(root_entry));
+}
+
+#[test]
+fn test_root_entry_without_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_name("venv")
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.find_nearest("/home/foo/bar"), None);
+
+    let root_entry = root.path().join("venv");
+    std::fs::create_dir(&root_entry).unwrap();
+
+    assert_eq!(
+        corpus.find_nearest("/home/foo/bar"),
+        Some(root_entry.clone())
+    );
+    assert_eq!(corpus.find_nearest("/home"), Some(root_entry));
+}
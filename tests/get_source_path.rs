@@ -14,3 +14,30 @@ fn test_with_extension_doesnt_exist() {
 
     assert_eq!(result, Some(PathBuf::from("/home/foo/bar")));
 }
+
+#[test]
+fn test_root_entry_with_extension() {
+    let corpus = builder()
+        .with_root("/home/.local/share")
+        .relative_to("/home")
+        .with_name("sauce")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    let result = corpus.get_source_path("/home/.local/share/sauce.toml");
+
+    assert_eq!(result, Some(PathBuf::from("/home")));
+}
+
+#[test]
+fn test_root_entry_without_extension() {
+    let corpus = builder()
+        .with_root("/home/.local/share")
+        .relative_to("/home")
+        .with_name("venv")
+        .build()
+        .unwrap();
+    let result = corpus.get_source_path("/home/.local/share/venv");
+
+    assert_eq!(result, Some(PathBuf::from("/home")));
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::{builder, GitRepo};
+
+#[test]
+fn test_git_repo() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("git")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.git_repo("/home/foo"),
+        GitRepo {
+            git_dir: root.path().join("foo.git"),
+            work_tree: PathBuf::from("/home/foo"),
+        }
+    );
+}
+
+#[test]
+fn test_find_git_repo() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("git")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo.git")).unwrap();
+    fs::write(root.path().join("foo.git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+    // An entry which isn't a git dir is skipped over.
+    fs::create_dir_all(root.path().join("foo/bar.git")).unwrap();
+
+    let repo = corpus.find_git_repo("/home/foo/bar/baz").unwrap();
+    assert_eq!(repo.git_dir, root.path().join("foo.git"));
+    assert_eq!(repo.work_tree, PathBuf::from("/home/foo"));
+    assert_eq!(
+        repo.env(),
+        [
+            ("GIT_DIR", &repo.git_dir),
+            ("GIT_WORK_TREE", &repo.work_tree)
+        ]
+    );
+
+    assert_eq!(corpus.find_git_repo("/home/other"), None);
+}
@@ -0,0 +1,234 @@
+#![cfg(all(feature = "binary", unix))]
+
+use assert_cmd::Command;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A `venv` profile rooted at `data`, mirroring the `source` directory, with
+/// an entry for `source/foo` (and one for `source` itself, out of reach of
+/// `source/other/deep`).
+struct Fixture {
+    config: TempDir,
+    data: TempDir,
+    source: TempDir,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let fixture = Self {
+            config: tempfile::tempdir().unwrap(),
+            data: tempfile::tempdir().unwrap(),
+            source: tempfile::tempdir().unwrap(),
+        };
+        std::fs::create_dir_all(fixture.config.path().join("corpus")).unwrap();
+        std::fs::write(
+            fixture.config.path().join("corpus/config.toml"),
+            format!(
+                r#"
+                [profiles.venv]
+                name = "venv"
+                kind = "{}"
+                relative_to = "{}"
+                max_depth = 1
+                "#,
+                fixture.data.path().display(),
+                fixture.source().display(),
+            ),
+        )
+        .unwrap();
+        std::fs::create_dir_all(fixture.source().join("foo/bar")).unwrap();
+        std::fs::create_dir_all(fixture.source().join("other/deep")).unwrap();
+        std::fs::create_dir_all(fixture.entry()).unwrap();
+        fixture
+    }
+
+    fn source(&self) -> PathBuf {
+        self.source.path().canonicalize().unwrap()
+    }
+
+    fn entry(&self) -> PathBuf {
+        self.data.path().canonicalize().unwrap().join("venv/foo")
+    }
+
+    /// Runs `script` in `shell`, after evaluating the script generated for it.
+    fn run(&self, shell: &str, init: &str, script: &str) -> Command {
+        let mut cmd = Command::new(shell);
+        cmd.env("XDG_CONFIG_HOME", self.config.path())
+            .env("CORPUS", assert_cmd::cargo::cargo_bin("corpus"))
+            .env("SRC", self.source())
+            .arg("-c")
+            .arg(format!("{}\n{}", init, script));
+        cmd
+    }
+}
+
+/// Whether `shell` is installed, as the tests running it are skipped if not.
+fn has_shell(shell: &str) -> bool {
+    let found = std::process::Command::new(shell)
+        .args(["-c", "true"])
+        .status()
+        .is_ok();
+    if !found {
+        eprintln!("skipped: {} isn't installed", shell);
+    }
+    found
+}
+
+fn lines(paths: &[&Path]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("{}\n", path.display()))
+        .collect()
+}
+
+#[test]
+fn bash() {
+    let fixture = Fixture::new();
+    let init = r#"eval "$("$CORPUS" --profile venv init bash)""#;
+
+    fixture
+        .run(
+            "bash",
+            init,
+            r#"
+            cd "$SRC/foo/bar" && eval "$PROMPT_COMMAND"
+            echo "$CORPUS_VENV_DIR"
+            echo "$CORPUS_VENV_SOURCE"
+            corpus-cd && pwd
+            corpus-cd && pwd
+            cd "$SRC/other/deep" && eval "$PROMPT_COMMAND"
+            echo "${CORPUS_VENV_DIR-unset}"
+            "#,
+        )
+        .assert()
+        .success()
+        .stdout(
+            lines(&[
+                &fixture.entry(),
+                &fixture.source().join("foo"),
+                &fixture.entry(),
+                &fixture.source().join("foo"),
+            ]) + "unset\n",
+        );
+}
+
+#[test]
+fn bash_without_entry() {
+    let fixture = Fixture::new();
+    let init = r#"eval "$("$CORPUS" --profile venv init bash --cd jump)""#;
+
+    fixture
+        .run("bash", init, r#"cd "$SRC/other/deep" && jump"#)
+        .assert()
+        .code(1)
+        .stderr(format!(
+            "jump: no entry for {}\n",
+            fixture.source().join("other/deep").display()
+        ));
+}
+
+/// Only checks that the scripts are generated, as running them needs the
+/// shells themselves (see `zsh` and `fish`).
+#[test]
+fn init_scripts() {
+    let fixture = Fixture::new();
+    for (shell, hook) in [("zsh", "chpwd"), ("fish", "--on-variable PWD")].iter() {
+        let output = Command::cargo_bin("corpus")
+            .unwrap()
+            .env("XDG_CONFIG_HOME", fixture.config.path())
+            .args(["--profile", "venv", "init", shell])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let script = String::from_utf8(output).unwrap();
+        assert!(script.contains(hook), "{}", script);
+        assert!(script.contains("corpus-cd"), "{}", script);
+    }
+}
+
+#[test]
+fn init_needs_a_name() {
+    Command::cargo_bin("corpus")
+        .unwrap()
+        .args(["init", "bash"])
+        .assert()
+        .code(2);
+}
+
+/// A `--relative-to` path which isn't valid UTF-8 reaches `corpus` unchanged
+/// through the generated script.
+#[test]
+fn init_keeps_non_utf8_paths() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let fixture = Fixture::new();
+    let name = OsStr::from_bytes(b"caf\xe9");
+    let source = fixture.source().join(name).join("foo");
+    std::fs::create_dir_all(&source).unwrap();
+    let init =
+        r#"eval "$("$CORPUS" --name venv --kind "$DATA" --relative-to "$SRC/$NAME" init bash)""#;
+
+    let output = fixture
+        .run(
+            "bash",
+            init,
+            r#"cd "$SRC/$NAME/foo" && eval "$PROMPT_COMMAND" && echo "$CORPUS_VENV_SOURCE""#,
+        )
+        .env("DATA", fixture.data.path())
+        .env("NAME", name)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(output, [source.as_os_str().as_bytes(), b"\n"].concat());
+}
+
+#[test]
+fn zsh() {
+    if !has_shell("zsh") {
+        return;
+    }
+    let fixture = Fixture::new();
+    let init = r#"eval "$("$CORPUS" --profile venv init zsh)""#;
+
+    fixture
+        .run(
+            "zsh",
+            init,
+            r#"
+            cd "$SRC/foo/bar"
+            echo "$CORPUS_VENV_DIR"
+            corpus-cd && pwd
+            "#,
+        )
+        .assert()
+        .success()
+        .stdout(lines(&[&fixture.entry(), &fixture.source().join("foo")]));
+}
+
+#[test]
+fn fish() {
+    if !has_shell("fish") {
+        return;
+    }
+    let fixture = Fixture::new();
+    let init = r#""$CORPUS" --profile venv init fish | source"#;
+
+    fixture
+        .run(
+            "fish",
+            init,
+            r#"
+            cd "$SRC/foo/bar"
+            echo "$CORPUS_VENV_DIR"
+            corpus-cd; and pwd
+            "#,
+        )
+        .assert()
+        .success()
+        .stdout(lines(&[&fixture.entry(), &fixture.source().join("foo")]));
+}
@@ -1,4 +1,6 @@
+#[cfg(feature = "home")]
 use assert_cmd::Command;
+#[cfg(feature = "home")]
 use std::path::PathBuf;
 
 #[cfg(feature = "home")]
@@ -6,6 +8,7 @@ fn home() -> PathBuf {
     dirs_next::home_dir().unwrap()
 }
 
+#[cfg(feature = "home")]
 fn output(path: PathBuf) -> String {
     format!("{}\n", path.to_string_lossy())
 }
@@ -15,7 +18,7 @@ fn output(path: PathBuf) -> String {
 fn sauce_example() {
     let mut cmd = Command::cargo_bin("corpus").unwrap();
     let assert = cmd
-        .args(&[
+        .args([
             "-n",
             "sauce",
             "-e",
@@ -28,3 +31,279 @@ fn sauce_example() {
         .success()
         .stdout(output(home().join(".local/share/sauce/foo/bar.toml")));
 }
+
+#[test]
+#[cfg(feature = "binary")]
+fn nearest_max_depth() {
+    let root = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(root.path().join("foo")).unwrap();
+
+    let args = [
+        "--kind",
+        &root.path().to_string_lossy(),
+        "--nearest",
+        "-p",
+        &home().join("foo/bar/baz").to_string_lossy(),
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect::<Vec<String>>();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args(&args)
+        .assert()
+        .success()
+        .stdout(output(root.path().join("foo")));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args(&args)
+        .args(["--max-depth", "1"])
+        .assert()
+        .success()
+        .stdout("\n");
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args(&args)
+        .args(["--stop-at", &home().join("foo/bar").to_string_lossy()])
+        .assert()
+        .success()
+        .stdout("\n");
+}
+
+#[test]
+#[cfg(all(feature = "binary", unix))]
+fn lock_runs_command() {
+    let root = tempfile::tempdir().unwrap();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args([
+        "--kind",
+        &root.path().to_string_lossy(),
+        "-p",
+        &home().join("foo").to_string_lossy(),
+        "lock",
+        "--",
+        "sh",
+        "-c",
+        "echo locked; exit 3",
+    ])
+    .assert()
+    .code(3)
+    .stdout("locked\n");
+
+    assert!(root.path().join("foo.lock").exists());
+}
+
+#[cfg(feature = "binary")]
+fn write_profiles(config_dir: &std::path::Path, data_dir: &std::path::Path) {
+    std::fs::create_dir_all(config_dir.join("corpus")).unwrap();
+    std::fs::write(
+        config_dir.join("corpus/config.toml"),
+        format!(
+            r#"
+            [profiles.git]
+            name = "git"
+            ext = "git"
+            kind = "{}"
+            anchor = "nearest"
+
+            [profiles.venv]
+            name = "venv"
+            kind = "{}"
+            "#,
+            data_dir.display(),
+            data_dir.display(),
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn profiles() {
+    let config = tempfile::tempdir().unwrap();
+    let data = tempfile::tempdir().unwrap();
+    write_profiles(config.path(), data.path());
+    std::fs::create_dir_all(data.path().join("git/foo.git")).unwrap();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.env("XDG_CONFIG_HOME", config.path())
+        .args(["profiles", "list"])
+        .assert()
+        .success()
+        .stdout("git\nvenv\n");
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.env("XDG_CONFIG_HOME", config.path())
+        .args([
+            "--profile",
+            "git",
+            "-p",
+            &home().join("foo/bar").to_string_lossy(),
+        ])
+        .assert()
+        .success()
+        .stdout(output(data.path().join("git/foo.git")));
+
+    // Explicit flags take precedence over the profile.
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.env("XDG_CONFIG_HOME", config.path())
+        .args([
+            "--profile",
+            "venv",
+            "-n",
+            "other",
+            "-p",
+            &home().join("foo").to_string_lossy(),
+        ])
+        .assert()
+        .success()
+        .stdout(output(data.path().join("other/foo")));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.env("XDG_CONFIG_HOME", config.path())
+        .args(["--profile", "missing"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn relative_to() {
+    let root = tempfile::tempdir().unwrap();
+    let kind = root.path().to_string_lossy().to_string();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args([
+        "--kind",
+        &kind,
+        "--relative-to",
+        "/srv",
+        "-p",
+        "/srv/foo/bar",
+    ])
+    .assert()
+    .success()
+    .stdout(output(root.path().join("foo/bar")));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args([
+        "--kind",
+        &kind,
+        "--relative-to",
+        "/srv",
+        "--source-path",
+        "-p",
+        &root.path().join("foo/bar").to_string_lossy(),
+    ])
+    .assert()
+    .success()
+    .stdout(output(PathBuf::from("/srv/foo/bar")));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args([
+        "--kind",
+        &kind,
+        "--relative-to-kind",
+        "root",
+        "-p",
+        "/srv/foo",
+    ])
+    .assert()
+    .success()
+    .stdout(output(root.path().join("srv/foo")));
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn relative_to_git_root() {
+    let root = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(repo.path().join(".git")).unwrap();
+    std::fs::create_dir_all(repo.path().join("foo/bar")).unwrap();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.current_dir(repo.path().join("foo/bar"))
+        .args([
+            "--kind",
+            &root.path().to_string_lossy(),
+            "--relative-to-kind",
+            "git-root",
+        ])
+        .assert()
+        .success()
+        .stdout(output(root.path().join("foo/bar")));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.current_dir(root.path())
+        .args([
+            "--kind",
+            &root.path().to_string_lossy(),
+            "--relative-to-kind",
+            "git-root",
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn relative_to_git_root_rejects_batches() {
+    let root = tempfile::tempdir().unwrap();
+    let repo = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(repo.path().join(".git")).unwrap();
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.current_dir(repo.path())
+        .args([
+            "--kind",
+            &root.path().to_string_lossy(),
+            "--relative-to-kind",
+            "git-root",
+            "-p",
+            "foo",
+            "-p",
+            "bar",
+        ])
+        .assert()
+        .code(2);
+}
+
+#[test]
+#[cfg(feature = "binary")]
+fn env_profiles() {
+    let config = tempfile::tempdir().unwrap();
+    let data = tempfile::tempdir().unwrap();
+    write_profiles(config.path(), data.path());
+    std::fs::create_dir_all(data.path().join("git/foo.git")).unwrap();
+
+    let source = home().join("foo/bar");
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    let output = cmd
+        .env("XDG_CONFIG_HOME", config.path())
+        .args(["-p", &source.to_string_lossy(), "env", "git", "venv"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let git_dir = data.path().join("git/foo.git");
+    assert!(stdout.contains(&format!(
+        "export CORPUS_GIT_NEAREST='{}'\n",
+        git_dir.display()
+    )));
+    assert!(stdout.contains(&format!(
+        "export CORPUS_GIT_NEAREST_SOURCE='{}'\n",
+        home().join("foo").display()
+    )));
+    assert!(stdout.contains(&format!(
+        "export CORPUS_VENV_ENTRY='{}'\n",
+        data.path().join("venv/foo/bar").display()
+    )));
+    assert!(stdout.contains("export CORPUS_VENV_EXISTS='0'\n"));
+
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.env("XDG_CONFIG_HOME", config.path())
+        .args(["--profile", "git", "env", "venv"])
+        .assert()
+        .code(2);
+}
@@ -0,0 +1,91 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use corpus::{builder, CorpusError};
+
+#[test]
+fn test_write_atomic_creates_parents() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let path = corpus.write_atomic("/home/foo/bar", "first").unwrap();
+    assert_eq!(path, root.path().join("foo/bar.toml"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+    corpus.write_atomic("/home/foo/bar", "second").unwrap();
+    assert_eq!(corpus.read_to_string("/home/foo/bar").unwrap(), "second");
+    assert_eq!(corpus.read("/home/foo/bar").unwrap(), b"second".to_vec());
+
+    let leftovers: Vec<_> = fs::read_dir(root.path().join("foo"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(leftovers, vec!["bar.toml"]);
+}
+
+#[test]
+fn test_read_missing() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    match corpus.read("/home/missing") {
+        Err(CorpusError::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_open_or_create_does_not_truncate() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let mut file = corpus.open_or_create("/home/foo").unwrap();
+    file.write_all(b"data").unwrap();
+    drop(file);
+
+    let mut file = corpus.open_or_create("/home/foo").unwrap();
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "data");
+
+    file.seek(SeekFrom::End(0)).unwrap();
+    file.write_all(b"more").unwrap();
+    assert_eq!(corpus.read_to_string("/home/foo").unwrap(), "datamore");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_dir_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_dir_mode(0o700)
+        .build()
+        .unwrap();
+
+    corpus.write_atomic("/home/foo/bar", "").unwrap();
+
+    let mode = fs::metadata(root.path().join("foo"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o700);
+}
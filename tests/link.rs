@@ -0,0 +1,121 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use corpus::{builder, CorpusError};
+
+#[test]
+fn test_link_is_idempotent() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let project = source.path().join("project");
+    fs::create_dir(&project).unwrap();
+
+    let link = corpus.link(project.as_path(), ".venv").unwrap().unwrap();
+    assert_eq!(link.path, project.join(".venv"));
+    assert_eq!(link.entry, root.path().join("project"));
+    assert!(link.is_linked());
+
+    assert_eq!(
+        corpus.link(project.as_path(), ".venv").unwrap(),
+        Some(link.clone())
+    );
+    assert_eq!(corpus.links().unwrap(), vec![link]);
+}
+
+#[test]
+fn test_link_skips_real_files() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let project = source.path().join("project");
+    fs::create_dir_all(project.join(".venv")).unwrap();
+
+    assert_eq!(corpus.link(project.as_path(), ".venv").unwrap(), None);
+    assert!(corpus.links().unwrap().is_empty());
+}
+
+#[test]
+fn test_unlink() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let project = source.path().join("project");
+    fs::create_dir(&project).unwrap();
+    let link = corpus.link(project.as_path(), ".venv").unwrap().unwrap();
+
+    assert!(corpus.unlink(&link).unwrap());
+    assert!(fs::symlink_metadata(&link.path).is_err());
+    assert!(corpus.links().unwrap().is_empty());
+}
+
+#[test]
+fn test_prune_links() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let (kept, removed) = (source.path().join("kept"), source.path().join("removed"));
+    fs::create_dir(&kept).unwrap();
+    fs::create_dir(&removed).unwrap();
+    let kept = corpus.link(kept.as_path(), ".venv").unwrap().unwrap();
+    let removed_link = corpus.link(removed.as_path(), ".venv").unwrap().unwrap();
+
+    fs::remove_dir_all(&removed).unwrap();
+    assert_eq!(corpus.prune_links().unwrap(), vec![removed_link]);
+    assert_eq!(corpus.links().unwrap(), vec![kept]);
+}
+
+#[test]
+fn test_manifest_is_not_an_entry() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let project = source.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::create_dir(root.path().join("project")).unwrap();
+    corpus.link(project.as_path(), ".venv").unwrap();
+
+    assert!(corpus
+        .entries()
+        .all(|entry| entry.path != root.path().join(".corpus-links")));
+}
+
+#[test]
+fn test_link_names_stay_in_the_source_directory() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+    let project = source.path().join("project");
+    fs::create_dir(&project).unwrap();
+
+    for name in ["../.venv", "sub/.venv", "/tmp/.venv", ".."].iter() {
+        let error = corpus.link(project.as_path(), name).unwrap_err();
+        assert!(matches!(error, CorpusError::InvalidName(_)), "{}", name);
+    }
+    assert!(!source.path().join(".venv").exists());
+}
@@ -0,0 +1,136 @@
+use corpus::{builder, LockMode};
+
+#[test]
+fn test_lock_path() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let lock = corpus.lock("/home/foo/bar", LockMode::Exclusive).unwrap();
+    assert_eq!(lock.path(), root.path().join("foo/bar.toml.lock"));
+    assert_eq!(lock.mode(), LockMode::Exclusive);
+
+    // The root entry's lock is kept inside the root.
+    let lock = corpus.lock("/home", LockMode::Exclusive).unwrap();
+    assert_eq!(lock.path(), root.path().join(".lock"));
+}
+
+#[test]
+fn test_exclusive_excludes() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let lock = corpus.lock("/home/foo", LockMode::Exclusive).unwrap();
+    assert!(corpus
+        .try_lock("/home/foo", LockMode::Exclusive)
+        .unwrap()
+        .is_none());
+    assert!(corpus
+        .try_lock("/home/foo", LockMode::Shared)
+        .unwrap()
+        .is_none());
+
+    // Other entries are unaffected.
+    assert!(corpus
+        .try_lock("/home/bar", LockMode::Exclusive)
+        .unwrap()
+        .is_some());
+
+    drop(lock);
+    assert!(corpus
+        .try_lock("/home/foo", LockMode::Exclusive)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_shared_locks_coexist() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let _first = corpus.lock("/home/foo", LockMode::Shared).unwrap();
+    let second = corpus.try_lock("/home/foo", LockMode::Shared).unwrap();
+    assert!(second.is_some());
+    assert!(corpus
+        .try_lock("/home/foo", LockMode::Exclusive)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_lock_files_are_not_entries() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let foo = source.path().join("foo");
+    std::fs::create_dir(&foo).unwrap();
+    corpus.write_atomic(foo.as_path(), "").unwrap();
+    let _lock = corpus.lock(foo.as_path(), LockMode::Shared).unwrap();
+
+    let entries: Vec<_> = corpus.descendants(source.path()).map(|e| e.path).collect();
+    assert_eq!(entries, vec![root.path().join("foo")]);
+}
+
+#[test]
+fn test_remove_locks() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    drop(corpus.lock("/home/foo", LockMode::Exclusive).unwrap());
+    drop(corpus.lock("/home/baz", LockMode::Exclusive).unwrap());
+
+    corpus.remove_locks(&root.path().join("foo.toml")).unwrap();
+    assert!(!root.path().join("foo.toml.lock").exists());
+    assert!(root.path().join("baz.toml.lock").exists());
+
+    // Removing locks which don't exist is fine.
+    corpus.remove_locks(&root.path().join("foo.toml")).unwrap();
+}
+
+#[test]
+fn test_locks_source_resolves() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    let locks = source.path().join(".locks");
+    std::fs::create_dir(&locks).unwrap();
+    std::fs::create_dir(root.path().join(".locks")).unwrap();
+    let _lock = corpus.lock(locks.as_path(), LockMode::Shared).unwrap();
+    let _root_lock = corpus.lock(source.path(), LockMode::Shared).unwrap();
+
+    let entries: Vec<_> = corpus.descendants(source.path()).map(|e| e.path).collect();
+    assert_eq!(entries, vec![root.path().join(".locks")]);
+    assert_eq!(
+        corpus.get_source_path(root.path().join(".locks")),
+        Some(locks)
+    );
+}
@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use corpus::builder;
+
+#[test]
+fn test_map_many_keeps_order() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    let result = corpus.map_many(&["/home/b", "/home/a", "/elsewhere/c"]);
+
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/config/b.toml"),
+            PathBuf::from("/config/a.toml"),
+            PathBuf::from("/elsewhere/c.toml"),
+        ]
+    );
+}
+
+#[test]
+fn test_map_many_matches_path() {
+    let current_dir = std::env::current_dir().unwrap();
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(&current_dir)
+        .build()
+        .unwrap();
+
+    let inputs = [Path::new("foo"), Path::new("./bar/../baz"), &current_dir];
+    let expected: Vec<_> = inputs.iter().map(|input| corpus.path(*input)).collect();
+
+    assert_eq!(corpus.map_many(&inputs), expected);
+    assert_eq!(
+        expected,
+        vec![
+            PathBuf::from("/config/foo"),
+            PathBuf::from("/config/baz"),
+            PathBuf::from("/config"),
+        ]
+    );
+}
+
+#[test]
+fn test_map_many_empty() {
+    let corpus = builder().build().unwrap();
+
+    assert!(corpus.map_many(Vec::<PathBuf>::new()).is_empty());
+}
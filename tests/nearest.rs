@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::builder;
+
+#[test]
+fn test_default_matches_existing() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo")).unwrap();
+
+    let result = corpus.nearest().find("/home/foo/bar/baz").unwrap();
+
+    assert_eq!(result.path, root.path().join("foo"));
+    assert_eq!(result.source_path, PathBuf::from("/home/foo"));
+    assert_eq!(result.depth, 2);
+}
+
+#[test]
+fn test_is_file() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo/bar")).unwrap();
+    fs::write(root.path().join("foo/bar/baz"), "").unwrap();
+
+    let result = corpus.nearest().is_file().find("/home/foo/bar/baz/qux");
+    assert_eq!(result.unwrap().path, root.path().join("foo/bar/baz"));
+
+    let result = corpus.nearest().is_dir().find("/home/foo/bar/baz/qux");
+    assert_eq!(result.unwrap().path, root.path().join("foo/bar"));
+}
+
+#[test]
+fn test_contains_and_non_empty() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo/bar")).unwrap();
+    fs::write(root.path().join("foo/HEAD"), "ref").unwrap();
+
+    let result = corpus.nearest().contains("HEAD").find("/home/foo/bar");
+    assert_eq!(result.unwrap().path, root.path().join("foo"));
+
+    let result = corpus.nearest().non_empty().find("/home/foo/bar");
+    assert_eq!(result.unwrap().path, root.path().join("foo"));
+}
+
+#[test]
+fn test_custom_predicate() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo/bar")).unwrap();
+
+    let result = corpus
+        .nearest()
+        .matching(|path| path.ends_with("foo"))
+        .find("/home/foo/bar");
+    assert_eq!(result.unwrap().path, root.path().join("foo"));
+}
+
+#[test]
+fn test_max_depth() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo")).unwrap();
+
+    assert_eq!(
+        corpus.nearest().max_depth(1).find("/home/foo/bar/baz"),
+        None
+    );
+    assert!(corpus
+        .nearest()
+        .max_depth(2)
+        .find("/home/foo/bar/baz")
+        .is_some());
+}
+
+#[test]
+fn test_stop_at_marker() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .build()
+        .unwrap();
+
+    fs::create_dir_all(root.path().join("repo")).unwrap();
+    fs::create_dir_all(source.path().join("repo/sub/.git")).unwrap();
+
+    let input = source.path().join("repo/sub/dir");
+    assert_eq!(corpus.nearest().stop_at_marker(".git").find(&*input), None);
+
+    let result = corpus.nearest().find(&*input).unwrap();
+    assert_eq!(result.source_path, source.path().join("repo"));
+}
+
+#[test]
+fn test_stop_at_boundary() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .build()
+        .unwrap();
+    fs::create_dir_all(root.path().join("foo")).unwrap();
+    fs::create_dir_all(root.path().join("foo/bar")).unwrap();
+
+    let result = corpus
+        .nearest()
+        .stop_at("/home/foo/bar")
+        .find("/home/foo/bar/baz");
+    assert_eq!(result.unwrap().source_path, PathBuf::from("/home/foo/bar"));
+
+    let result = corpus
+        .nearest()
+        .is_file()
+        .stop_at("/home/foo/bar")
+        .find("/home/foo/bar/baz");
+    assert_eq!(result, None);
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::builder;
+
+#[test]
+fn test_case_folding() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/Home")
+        .with_extension("toml")
+        .with_case_folding()
+        .build()
+        .unwrap();
+
+    let expected = PathBuf::from("/home/.config/projects/foo.toml");
+    assert_eq!(corpus.path("/Home/Projects/FOO"), expected);
+    assert_eq!(corpus.path("/Home/projects/foo"), expected);
+}
+
+#[test]
+fn test_case_folding_keeps_source_casing() {
+    let root = tempfile::tempdir().unwrap();
+    let source = tempfile::tempdir().unwrap();
+    fs::create_dir_all(source.path().join("Projects/FOO")).unwrap();
+
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to(source.path())
+        .with_case_folding()
+        .build()
+        .unwrap();
+
+    let foo = source.path().join("Projects/FOO");
+    let path = corpus.path(foo.as_path());
+    assert_eq!(path, root.path().join("projects/foo"));
+    assert_eq!(corpus.get_source_path(&path), Some(foo));
+
+    // Sources which no longer exist keep the folded casing.
+    let gone = root.path().join("projects/gone");
+    assert_eq!(
+        corpus.get_source_path(gone),
+        Some(source.path().join("Projects/gone"))
+    );
+
+    fs::create_dir_all(&path).unwrap();
+    let orphaned: Vec<_> = corpus
+        .descendants(source.path())
+        .filter(|entry| !entry.source_path.exists())
+        .collect();
+    assert!(orphaned.is_empty());
+}
+
+#[test]
+fn test_case_folding_outside_relative_to() {
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .with_case_folding()
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.path("/Root/Foo"), PathBuf::from("/Root/Foo"));
+}
+
+#[test]
+#[cfg(feature = "unicode")]
+fn test_unicode_normalization() {
+    use corpus::UnicodeForm;
+
+    let composed = "/home/caf\u{e9}";
+    let decomposed = "/home/cafe\u{301}";
+
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .with_unicode_normalization(UnicodeForm::Nfc)
+        .build()
+        .unwrap();
+    let expected = PathBuf::from("/home/.config/caf\u{e9}");
+    assert_eq!(corpus.path(composed), expected);
+    assert_eq!(corpus.path(decomposed), expected);
+
+    let corpus = builder()
+        .with_root("/home/.config")
+        .relative_to("/home")
+        .with_unicode_normalization(UnicodeForm::Nfd)
+        .build()
+        .unwrap();
+    let expected = PathBuf::from("/home/.config/cafe\u{301}");
+    assert_eq!(corpus.path(composed), expected);
+    assert_eq!(corpus.path(decomposed), expected);
+}
+
+#[test]
+fn test_collisions() {
+    let root = tempfile::tempdir().unwrap();
+    fs::create_dir_all(root.path().join("projects")).unwrap();
+    fs::write(root.path().join("projects/Foo.toml"), "").unwrap();
+    fs::write(root.path().join("projects/foo.toml"), "").unwrap();
+    fs::write(root.path().join("projects/bar.toml"), "").unwrap();
+
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    assert_eq!(corpus.collisions(), vec![]);
+
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_case_folding()
+        .build()
+        .unwrap();
+    let collisions = corpus.collisions();
+
+    assert_eq!(collisions.len(), 1);
+    assert_eq!(collisions[0].path, root.path().join("projects/foo.toml"));
+
+    let sources: Vec<PathBuf> = collisions[0]
+        .entries
+        .iter()
+        .map(|entry| entry.source_path.clone())
+        .collect();
+    assert_eq!(
+        sources,
+        vec![
+            PathBuf::from("/home/projects/Foo"),
+            PathBuf::from("/home/projects/foo"),
+        ]
+    );
+}
@@ -0,0 +1,180 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::{builder, PathLimits};
+
+#[test]
+fn test_fits() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(PathLimits::default())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/home/foo/bar"),
+        root.path().join("foo/bar.toml")
+    );
+    assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_path_overflow() {
+    let root = tempfile::tempdir().unwrap();
+    let limits = PathLimits {
+        max_path: root.path().as_os_str().len() + 40,
+        max_name: 255,
+    };
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(limits)
+        .build()
+        .unwrap();
+
+    let source = PathBuf::from("/home/projects/some/deeply/nested/source/directory");
+    let path = corpus.path(&*source);
+
+    // Computing the path alone records nothing, so it can't be resolved.
+    assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+    assert_eq!(corpus.get_source_path(&path), None);
+    assert_eq!(corpus.prepare_path(&*source).unwrap(), path);
+
+    assert!(limits.fits(&path));
+    assert!(path.starts_with(root.path().join("projects/some")));
+    assert_eq!(path.extension().unwrap(), "toml");
+    assert_eq!(corpus.path(&*source), path);
+    assert_eq!(corpus.get_source_path(&path), Some(source));
+}
+
+#[test]
+fn test_name_overflow() {
+    let root = tempfile::tempdir().unwrap();
+    let limits = PathLimits {
+        max_path: 4096,
+        max_name: 24,
+    };
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(limits)
+        .build()
+        .unwrap();
+
+    let source = PathBuf::from("/home/foo/a-component-which-is-far-too-long/bar");
+    let path = corpus.prepare_path(&*source).unwrap();
+
+    assert!(limits.fits(&path));
+    assert_eq!(path.parent().unwrap(), root.path().join("foo"));
+    assert_eq!(corpus.get_source_path(&path), Some(source));
+}
+
+#[test]
+fn test_entries_resolve_shortened() {
+    let root = tempfile::tempdir().unwrap();
+    let limits = PathLimits {
+        max_path: 4096,
+        max_name: 24,
+    };
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(limits)
+        .build()
+        .unwrap();
+
+    let source = PathBuf::from("/home/foo/a-component-which-is-far-too-long");
+    let path = corpus.write_atomic(&*source, "").unwrap();
+
+    let entries: Vec<(PathBuf, PathBuf)> = corpus
+        .entries()
+        .map(|entry| (entry.path, entry.source_path))
+        .collect();
+    assert_eq!(entries, vec![(path, source)]);
+}
+
+#[test]
+fn test_source_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("source")
+        .with_path_limits(PathLimits {
+            max_path: 4096,
+            max_name: 24,
+        })
+        .build()
+        .unwrap();
+
+    let source = PathBuf::from("/home/a-component-which-is-far-too-long");
+    let path = corpus.write_atomic(&*source, "contents").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "contents");
+    assert_eq!(corpus.get_source_path(&path), Some(source.clone()));
+
+    let entries: Vec<PathBuf> = corpus.entries().map(|entry| entry.source_path).collect();
+    assert_eq!(entries, vec![source]);
+}
+
+#[test]
+fn test_record_path() {
+    let root = tempfile::tempdir().unwrap();
+    let limits = PathLimits {
+        max_path: root.path().as_os_str().len() + 30,
+        max_name: 255,
+    };
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(limits)
+        .build()
+        .unwrap();
+
+    // Paths which fit are left alone.
+    assert_eq!(
+        corpus.record_path("/home/foo").unwrap(),
+        root.path().join("foo.toml")
+    );
+    assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+
+    let source = PathBuf::from("/home/some/deeply/nested/source/directory");
+    let path = corpus.record_path(&*source).unwrap();
+    assert_eq!(path, corpus.path(&*source));
+    assert!(!path.exists());
+    assert_eq!(corpus.get_source_path(&path), Some(source));
+}
+
+#[test]
+fn test_move_entry_moves_the_sidecar() {
+    let root = tempfile::tempdir().unwrap();
+    let limits = PathLimits {
+        max_path: root.path().as_os_str().len() + 30,
+        max_name: 255,
+    };
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_path_limits(limits)
+        .build()
+        .unwrap();
+
+    let old = PathBuf::from("/home/some/deeply/nested/source/directory");
+    let new = PathBuf::from("/home/some/deeply/nested/source/elsewhere");
+    let path = corpus.prepare_path(&*old).unwrap();
+    fs::write(&path, "data").unwrap();
+
+    let moved = corpus.move_entry(&*old, &*new).unwrap();
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "data");
+    assert_eq!(corpus.get_source_path(&moved), Some(new));
+    assert_eq!(corpus.get_source_path(&path), None);
+    assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 2);
+}
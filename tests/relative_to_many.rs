@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+
+use corpus::builder;
+
+#[test]
+fn test_path() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![
+            ("/home/me", "home"),
+            ("/srv/checkouts", "srv"),
+            ("/srv/checkouts/special", "special"),
+        ])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/home/me/foo"),
+        PathBuf::from("/config/home/foo.toml")
+    );
+    assert_eq!(
+        corpus.path("/srv/checkouts/foo/bar"),
+        PathBuf::from("/config/srv/foo/bar.toml")
+    );
+    assert_eq!(
+        corpus.path("/srv/checkouts"),
+        PathBuf::from("/config/srv.toml")
+    );
+}
+
+#[test]
+fn test_longest_base_wins() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![
+            ("/home/me", "home"),
+            ("/srv/checkouts", "srv"),
+            ("/srv/checkouts/special", "special"),
+        ])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/srv/checkouts/special/foo"),
+        PathBuf::from("/config/special/foo.toml")
+    );
+}
+
+#[test]
+fn test_outside_every_base() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![
+            ("/home/me", "home"),
+            ("/srv/checkouts", "srv"),
+            ("/srv/checkouts/special", "special"),
+        ])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.path("/etc/foo"), PathBuf::from("/etc/foo.toml"));
+    assert_eq!(corpus.ancestors("/etc/foo").count(), 0);
+}
+
+#[test]
+fn test_get_source_path() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![
+            ("/home/me", "home"),
+            ("/srv/checkouts", "srv"),
+            ("/srv/checkouts/special", "special"),
+        ])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.get_source_path("/config/home/foo.toml"),
+        Some(PathBuf::from("/home/me/foo"))
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/srv/foo/bar.toml"),
+        Some(PathBuf::from("/srv/checkouts/foo/bar"))
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/special/foo.toml"),
+        Some(PathBuf::from("/srv/checkouts/special/foo"))
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/srv.toml"),
+        Some(PathBuf::from("/srv/checkouts"))
+    );
+    assert_eq!(corpus.get_source_path("/config/unknown/foo.toml"), None);
+}
+
+#[test]
+fn test_ancestors_stop_at_base() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![
+            ("/home/me", "home"),
+            ("/srv/checkouts", "srv"),
+            ("/srv/checkouts/special", "special"),
+        ])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    let result: Vec<PathBuf> = corpus
+        .ancestors("/srv/checkouts/foo")
+        .map(|entry| entry.source_path)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/srv/checkouts/foo"),
+            PathBuf::from("/srv/checkouts"),
+        ]
+    );
+}
+
+#[test]
+fn test_entries() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to_many(vec![("/home/me", "home"), ("/srv/checkouts", "srv")])
+        .with_extension("toml")
+        .build()
+        .unwrap();
+
+    fs::create_dir_all(root.path().join("home")).unwrap();
+    fs::create_dir_all(root.path().join("srv")).unwrap();
+    fs::write(root.path().join("home.toml"), "").unwrap();
+    fs::write(root.path().join("home/foo.toml"), "").unwrap();
+    fs::write(root.path().join("srv/bar.toml"), "").unwrap();
+
+    let result: Vec<PathBuf> = corpus.entries().map(|entry| entry.source_path).collect();
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/home/me"),
+            PathBuf::from("/home/me/foo"),
+            PathBuf::from("/srv/checkouts/bar"),
+        ]
+    );
+}
+
+#[test]
+fn test_case_folded_subtrees() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to_many(vec![("/home/me", "Home"), ("/srv/Checkouts", "SRV")])
+        .with_case_folding()
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/home/me/Foo"),
+        PathBuf::from("/config/home/foo")
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/home/foo"),
+        Some(PathBuf::from("/home/me/foo"))
+    );
+    assert_eq!(
+        corpus.path("/srv/Checkouts/Bar"),
+        PathBuf::from("/config/srv/bar")
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/srv/bar"),
+        Some(PathBuf::from("/srv/Checkouts/bar"))
+    );
+}
@@ -0,0 +1,123 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::PathBuf;
+
+use corpus::{builder, MaybePath, Resolution};
+
+/// Produces `<tmp>/data/src/project` and a `<tmp>/home/src -> <tmp>/data/src` link.
+fn symlinked_tree() -> (tempfile::TempDir, PathBuf) {
+    let tmp = tempfile::tempdir().unwrap();
+    let tmp_path = tmp.path().canonicalize().unwrap();
+    fs::create_dir_all(tmp_path.join("data/src/project")).unwrap();
+    fs::create_dir_all(tmp_path.join("home")).unwrap();
+    symlink(tmp_path.join("data/src"), tmp_path.join("home/src")).unwrap();
+    (tmp, tmp_path)
+}
+
+#[test]
+fn test_lexical_keeps_symlinks() {
+    let (_tmp, tmp) = symlinked_tree();
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(&tmp)
+        .with_extension("toml")
+        .with_resolution(Resolution::Lexical)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path(&*tmp.join("home/src/project")),
+        PathBuf::from("/config/home/src/project.toml")
+    );
+    assert_eq!(
+        corpus.path(&*tmp.join("data/src/project")),
+        PathBuf::from("/config/data/src/project.toml")
+    );
+}
+
+#[test]
+fn test_canonical_resolves_symlinks() {
+    let (_tmp, tmp) = symlinked_tree();
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(&tmp)
+        .with_extension("toml")
+        .with_resolution(Resolution::Canonical)
+        .build()
+        .unwrap();
+
+    let expected = PathBuf::from("/config/data/src/project.toml");
+    assert_eq!(corpus.path(&*tmp.join("home/src/project")), expected);
+    assert_eq!(corpus.path(&*tmp.join("data/src/project")), expected);
+}
+
+#[test]
+fn test_canonical_missing_components() {
+    let (_tmp, tmp) = symlinked_tree();
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(&tmp)
+        .with_extension("toml")
+        .with_resolution(Resolution::Canonical)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path(&*tmp.join("home/src/missing/../new/dir")),
+        PathBuf::from("/config/data/src/new/dir.toml")
+    );
+}
+
+#[test]
+fn test_canonical_relative_to_symlink() {
+    let (_tmp, tmp) = symlinked_tree();
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(tmp.join("home/src"))
+        .with_extension("toml")
+        .with_resolution(Resolution::Canonical)
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.relative_path, tmp.join("data/src"));
+    assert_eq!(
+        corpus.path(&*tmp.join("data/src/project")),
+        PathBuf::from("/config/project.toml")
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/project.toml"),
+        Some(tmp.join("data/src/project"))
+    );
+}
+
+#[test]
+fn test_prefer_logical_uses_pwd() {
+    let (_tmp, tmp) = symlinked_tree();
+    let current_dir = std::env::current_dir().unwrap();
+    symlink(&current_dir, tmp.join("home/cwd")).unwrap();
+    std::env::set_var("PWD", tmp.join("home/cwd"));
+
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to(&tmp)
+        .with_extension("toml")
+        .with_resolution(Resolution::PreferLogical)
+        .build()
+        .unwrap();
+    assert_eq!(
+        corpus.path(MaybePath::CurrentDir),
+        PathBuf::from("/config/home/cwd.toml")
+    );
+    assert_eq!(
+        corpus.path("child"),
+        PathBuf::from("/config/home/cwd/child.toml")
+    );
+
+    std::env::set_var("PWD", tmp.join("data"));
+    assert_eq!(
+        corpus.path(MaybePath::CurrentDir),
+        corpus.path(&*current_dir)
+    );
+}
@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use corpus::{builder, Rewrite};
+
+#[test]
+fn test_prefix() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_extension("toml")
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/workspaces/*", "src/checkout"))
+        .build()
+        .unwrap();
+
+    let expected = PathBuf::from("/config/work/foo/bar.toml");
+    assert_eq!(corpus.path("/home/me/work/foo/bar"), expected);
+    assert_eq!(corpus.path("/mnt/work/foo/bar"), expected);
+    assert_eq!(corpus.path("/mnt/work"), PathBuf::from("/config/work.toml"));
+}
+
+#[test]
+fn test_glob() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_extension("toml")
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/workspaces/*", "src/checkout"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/workspaces/project/src"),
+        PathBuf::from("/config/src/checkout/src.toml")
+    );
+    assert_eq!(
+        corpus.path("/workspaces/other"),
+        PathBuf::from("/config/src/checkout.toml")
+    );
+    assert_eq!(
+        corpus.path("/workspaces"),
+        PathBuf::from("/workspaces.toml")
+    );
+}
+
+#[test]
+fn test_unmatched() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_extension("toml")
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/workspaces/*", "src/checkout"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/home/me/other"),
+        PathBuf::from("/config/other.toml")
+    );
+    assert_eq!(corpus.path("/srv/other"), PathBuf::from("/srv/other.toml"));
+}
+
+#[test]
+fn test_first_rule_wins() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_rewrite(Rewrite::prefix("/home/me/work/special", "special"))
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.path("/home/me/work/special/foo"),
+        PathBuf::from("/config/special/foo")
+    );
+    assert_eq!(
+        corpus.path("/home/me/work/foo"),
+        PathBuf::from("/config/work/foo")
+    );
+}
+
+#[test]
+fn test_get_source_path_inverts_first_prefix_rule() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_extension("toml")
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/workspaces/*", "src/checkout"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        corpus.get_source_path("/config/work/foo/bar.toml"),
+        Some(PathBuf::from("/home/me/work/foo/bar"))
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/src/checkout/src.toml"),
+        Some(PathBuf::from("/home/me/src/checkout/src"))
+    );
+    assert_eq!(
+        corpus.get_source_path("/config/other.toml"),
+        Some(PathBuf::from("/home/me/other"))
+    );
+}
+
+#[test]
+fn test_ancestors_stop_at_rewrite_prefix() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_extension("toml")
+        .with_rewrite(Rewrite::prefix("/home/me/work", "work"))
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/workspaces/*", "src/checkout"))
+        .build()
+        .unwrap();
+    let result: Vec<PathBuf> = corpus
+        .ancestors("/mnt/work/foo")
+        .map(|entry| entry.path)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            PathBuf::from("/config/work/foo.toml"),
+            PathBuf::from("/config/work.toml"),
+        ]
+    );
+}
+
+#[test]
+fn test_case_folded_target() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home/me")
+        .with_rewrite(Rewrite::prefix("/mnt/work", "Work"))
+        .with_case_folding()
+        .build()
+        .unwrap();
+
+    assert_eq!(corpus.path("/mnt/work/X"), PathBuf::from("/config/work/x"));
+    assert_eq!(
+        corpus.get_source_path("/config/work/x"),
+        Some(PathBuf::from("/mnt/work/x"))
+    );
+}
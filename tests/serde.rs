@@ -0,0 +1,121 @@
+#![cfg(feature = "serde")]
+
+use corpus::{builder, Corpus, CorpusBuilder, CorpusConfig, Resolution, Rewrite, RootLocation};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn round_trip(root: RootLocation, expected: &str) {
+    let wrapper = Wrapper { value: root };
+    let serialized = toml::to_string(&wrapper).unwrap();
+    assert_eq!(serialized, format!("value = \"{}\"\n", expected));
+
+    let deserialized: Wrapper<RootLocation> = toml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, wrapper);
+}
+
+#[test]
+fn test_root_raw() {
+    round_trip(RootLocation::from("/config"), "/config");
+    round_trip(RootLocation::from("config"), "config");
+}
+
+#[test]
+#[cfg(feature = "xdg")]
+fn test_root_xdg() {
+    round_trip(RootLocation::XDGData, "xdg-data");
+    round_trip(RootLocation::XDGConfig, "xdg-config");
+    round_trip(RootLocation::XDGCache, "xdg-cache");
+}
+
+#[test]
+#[cfg(feature = "xdg")]
+fn test_root_raw_named_like_xdg() {
+    let root = RootLocation::Raw(PathBuf::from("xdg-data"));
+    let serialized = toml::to_string(&Wrapper { value: &root }).unwrap();
+    assert_eq!(serialized, "value = \"./xdg-data\"\n");
+
+    let deserialized: Wrapper<RootLocation> = toml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.value.path().unwrap(), root.path().unwrap());
+}
+
+#[test]
+fn test_corpus() {
+    let corpus = builder()
+        .with_root("/config")
+        .relative_to("/home")
+        .with_extension("toml")
+        .with_resolution(Resolution::PreferLogical)
+        .with_case_folding()
+        .with_rewrite(Rewrite::prefix("/mnt/work", "work"))
+        .with_rewrite(Rewrite::glob("/mnt/*/work", "work"))
+        .with_dir_mode(0o700)
+        .build()
+        .unwrap();
+
+    let serialized = toml::to_string(&corpus).unwrap();
+    let deserialized: Corpus = toml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, corpus);
+}
+
+#[test]
+fn test_corpus_defaults() {
+    let corpus: Corpus = toml::from_str(
+        r#"
+        root_location = "/config"
+        relative_path = "/home"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(corpus, Corpus::new("/config", "/home", None));
+}
+
+#[test]
+fn test_config() {
+    let config: CorpusConfig = toml::from_str(
+        r#"
+        root = "/config"
+        relative_to = "/home"
+        name = "project"
+        extension = "toml"
+        resolution = "canonical"
+        fold_case = true
+
+        [[rewrites]]
+        prefix = "/mnt/work"
+        target = "work"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config,
+        CorpusConfig {
+            root: Some(RootLocation::from("/config")),
+            relative_to: Some(PathBuf::from("/home")),
+            name: Some("project".to_string()),
+            extension: Some("toml".to_string()),
+            resolution: Resolution::Canonical,
+            fold_case: true,
+            rewrites: vec![Rewrite::prefix("/mnt/work", "work")],
+            ..CorpusConfig::default()
+        }
+    );
+
+    let serialized = toml::to_string(&config).unwrap();
+    assert_eq!(toml::from_str::<CorpusConfig>(&serialized).unwrap(), config);
+
+    let corpus = CorpusBuilder::from(config).build().unwrap();
+    assert_eq!(
+        corpus.path("/home/Foo"),
+        PathBuf::from("/config/project/foo.toml")
+    );
+    assert_eq!(
+        corpus.path("/mnt/work/bar"),
+        PathBuf::from("/config/project/work/bar.toml")
+    );
+}
@@ -0,0 +1,124 @@
+#![cfg(any(feature = "toml", feature = "json", feature = "yaml", feature = "ron"))]
+
+use corpus::builder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Settings {
+    name: String,
+    values: Vec<u32>,
+}
+
+fn settings() -> Settings {
+    Settings {
+        name: "foo".to_string(),
+        values: vec![1, 2, 3],
+    }
+}
+
+fn round_trip(extension: &str) {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension(extension)
+        .build()
+        .unwrap();
+
+    let path = corpus.store("/home/foo", &settings()).unwrap();
+    assert_eq!(path, root.path().join(format!("foo.{}", extension)));
+    assert_eq!(corpus.load("/home/foo").unwrap(), Some(settings()));
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn test_toml() {
+    round_trip("toml");
+
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("toml")
+        .build()
+        .unwrap();
+    corpus.store("/home/foo", &settings()).unwrap();
+    assert_eq!(
+        corpus.read_to_string("/home/foo").unwrap(),
+        "name = \"foo\"\nvalues = [\n    1,\n    2,\n    3,\n]\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_json() {
+    round_trip("json");
+}
+
+#[test]
+#[cfg(feature = "yaml")]
+fn test_yaml() {
+    round_trip("yaml");
+    round_trip("yml");
+}
+
+#[test]
+#[cfg(feature = "ron")]
+fn test_ron() {
+    round_trip("ron");
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_load_missing() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("json")
+        .build()
+        .unwrap();
+
+    let loaded: Option<Settings> = corpus.load("/home/missing").unwrap();
+    assert_eq!(loaded, None);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_load_invalid() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("json")
+        .build()
+        .unwrap();
+    corpus.write_atomic("/home/foo", "{").unwrap();
+
+    match corpus.load::<Settings>("/home/foo") {
+        Err(corpus::CorpusError::Deserialize { path, .. }) => {
+            assert_eq!(path, root.path().join("foo.json"))
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_unsupported_extension() {
+    let root = tempfile::tempdir().unwrap();
+    let corpus = builder()
+        .with_root(root.path())
+        .relative_to("/home")
+        .with_extension("txt")
+        .build()
+        .unwrap();
+
+    match corpus.store("/home/foo", &settings()) {
+        Err(corpus::CorpusError::UnsupportedFormat(extension)) => {
+            assert_eq!(extension.as_deref(), Some("txt"))
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(!root.path().join("foo.txt").exists());
+}
@@ -0,0 +1,927 @@
+#![cfg(feature = "binary")]
+
+use assert_cmd::Command;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn output(path: PathBuf) -> String {
+    format!("{}\n", path.to_string_lossy())
+}
+
+/// A corpus rooted at `root`, mirroring the `source` directory.
+struct Fixture {
+    root: TempDir,
+    source: TempDir,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        Self {
+            root: tempfile::tempdir().unwrap(),
+            source: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    fn root(&self) -> &Path {
+        self.root.path()
+    }
+
+    fn source(&self) -> &Path {
+        self.source.path()
+    }
+
+    fn cmd(&self, args: &[&str]) -> Command {
+        let mut cmd = Command::cargo_bin("corpus").unwrap();
+        cmd.args([
+            "--kind",
+            &self.root().to_string_lossy(),
+            "--relative-to",
+            &self.source().to_string_lossy(),
+        ]);
+        cmd.args(args);
+        cmd
+    }
+
+    fn cmd_at(&self, args: &[&str], path: &str) -> Command {
+        let mut cmd = Command::cargo_bin("corpus").unwrap();
+        cmd.args([
+            "--kind",
+            &self.root().to_string_lossy(),
+            "--relative-to",
+            &self.source().to_string_lossy(),
+            "-p",
+            &self.source().join(path).to_string_lossy(),
+        ]);
+        cmd.args(args);
+        cmd
+    }
+}
+
+#[test]
+fn path() {
+    let fixture = Fixture::new();
+    fixture
+        .cmd_at(&["path"], "foo/bar")
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("foo/bar")));
+}
+
+#[test]
+fn path_records_shortened_sources() {
+    let fixture = Fixture::new();
+    let long = "x".repeat(300);
+
+    let assert = fixture.cmd_at(&["path"], &long).assert().success();
+    let entry = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let entry = entry.trim_end();
+    assert!(!entry.contains(&long));
+
+    fixture
+        .cmd(&["source", entry])
+        .assert()
+        .success()
+        .stdout(output(fixture.source().join(&long)));
+}
+
+#[test]
+fn nearest() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo")).unwrap();
+
+    fixture
+        .cmd_at(&["nearest"], "foo/bar")
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("foo")));
+
+    fixture
+        .cmd_at(&["nearest", "--max-depth", "0"], "foo/bar")
+        .assert()
+        .code(1)
+        .stdout("");
+}
+
+#[test]
+fn source() {
+    let fixture = Fixture::new();
+    let entry = fixture.root().join("foo/bar");
+
+    fixture
+        .cmd(&["source", &entry.to_string_lossy()])
+        .assert()
+        .success()
+        .stdout(output(fixture.source().join("foo/bar")));
+
+    fixture
+        .cmd(&["source", &fixture.source().to_string_lossy()])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn ensure_is_idempotent() {
+    let fixture = Fixture::new();
+    let entry = fixture.root().join("foo/bar");
+
+    for _ in 0..2 {
+        fixture
+            .cmd_at(&["ensure"], "foo/bar")
+            .assert()
+            .success()
+            .stdout(output(entry.clone()));
+    }
+    assert!(entry.is_dir());
+
+    fixture
+        .cmd_at(&["ensure", "--file"], "baz")
+        .assert()
+        .success();
+    assert!(fixture.root().join("baz").is_file());
+}
+
+#[test]
+#[cfg(unix)]
+fn ensure_initializes() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = Fixture::new();
+    let entry = fixture.root().join("foo");
+
+    fixture
+        .cmd_at(
+            &["ensure", "--mode", "0700", "--init", "echo init > {}/log"],
+            "foo",
+        )
+        .assert()
+        .success()
+        .stdout(output(entry.clone()));
+
+    let mode = std::fs::metadata(&entry).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+    assert_eq!(
+        std::fs::read_to_string(entry.join("log")).unwrap(),
+        "init\n"
+    );
+
+    fixture
+        .cmd_at(&["ensure", "--init", "exit 1"], "bar")
+        .assert()
+        .code(2);
+    assert!(!fixture.root().join("bar").exists());
+}
+
+#[test]
+fn ls() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo/bar")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("baz")).unwrap();
+
+    fixture
+        .cmd_at(&["ls"], "foo")
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}{}",
+            output(fixture.root().join("foo")),
+            output(fixture.root().join("foo/bar"))
+        ));
+
+    fixture
+        .cmd_at(&["ls", "--sources"], "foo")
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}{}",
+            output(fixture.source().join("foo")),
+            output(fixture.source().join("foo/bar"))
+        ));
+}
+
+/// Sets the mtime of `path` to 2021-06-01 12:00 UTC.
+fn set_mtime(path: &Path) {
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_622_548_800);
+    std::fs::File::open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+#[test]
+fn tree() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo/bar")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("baz")).unwrap();
+    std::fs::create_dir_all(fixture.source().join("foo")).unwrap();
+    std::fs::write(fixture.root().join("foo/bar/data"), [0; 1536]).unwrap();
+    std::fs::write(fixture.root().join("baz/data"), "baz").unwrap();
+    for path in ["", "foo", "foo/bar", "baz"].iter() {
+        set_mtime(&fixture.root().join(path));
+    }
+
+    fixture
+        .cmd(&["tree", "--all"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{} [dir, 1.5 KiB, 2021-06-01 12:00]\n  \
+             baz [dir, 3 B, 2021-06-01 12:00, orphaned]\n  \
+             foo [dir, 1.5 KiB, 2021-06-01 12:00]\n",
+            fixture.source().to_string_lossy()
+        ));
+}
+
+#[test]
+fn tree_filters() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("kept/nested")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("gone")).unwrap();
+    std::fs::create_dir_all(fixture.source().join("kept/nested")).unwrap();
+    for path in ["", "kept", "kept/nested", "gone"].iter() {
+        set_mtime(&fixture.root().join(path));
+    }
+
+    fixture
+        .cmd(&["tree", "--all", "--orphaned"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{} [dir, 0 B, 2021-06-01 12:00]\n  \
+             gone [dir, 0 B, 2021-06-01 12:00, orphaned]\n",
+            fixture.source().to_string_lossy()
+        ));
+
+    fixture
+        .cmd(&["tree", "--all", "--max-depth", "1"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{} [dir, 0 B, 2021-06-01 12:00]\n  \
+             gone [dir, 0 B, 2021-06-01 12:00, orphaned]\n  \
+             kept [dir, 0 B, 2021-06-01 12:00]\n",
+            fixture.source().to_string_lossy()
+        ));
+}
+
+#[test]
+fn prune() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("kept")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("gone/nested")).unwrap();
+    std::fs::create_dir_all(fixture.source().join("kept")).unwrap();
+    std::fs::write(fixture.root().join("gone.lock"), "").unwrap();
+    std::fs::write(fixture.root().join("kept.lock"), "").unwrap();
+
+    fixture
+        .cmd(&["prune", "--all", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("gone")));
+    assert!(fixture.root().join("gone").exists());
+
+    fixture
+        .cmd(&["prune", "--all"])
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("gone")));
+    assert!(!fixture.root().join("gone").exists());
+    assert!(fixture.root().join("kept").exists());
+    assert!(!fixture.root().join("gone.lock").exists());
+    assert!(fixture.root().join("kept.lock").exists());
+}
+
+#[test]
+fn prune_keeps_entry_data() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.source().join("proj")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("proj/bin")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("proj/lib")).unwrap();
+    std::fs::write(fixture.root().join("proj/pyvenv.cfg"), "").unwrap();
+
+    fixture
+        .cmd(&["tree", "--all", "--orphaned"])
+        .assert()
+        .success()
+        .stdout("");
+    fixture
+        .cmd(&["prune", "--all"])
+        .assert()
+        .success()
+        .stdout("");
+    assert!(fixture.root().join("proj/bin").exists());
+    assert!(fixture.root().join("proj/lib").exists());
+    assert!(fixture.root().join("proj/pyvenv.cfg").exists());
+}
+
+#[test]
+fn prune_keeps_directories_within_entries() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.source().join("proj")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("n/proj/cache/deep")).unwrap();
+
+    let cmd = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("corpus").unwrap();
+        cmd.args([
+            "--kind",
+            &fixture.root().to_string_lossy(),
+            "-n",
+            "n",
+            "--relative-to",
+            &fixture.source().to_string_lossy(),
+        ]);
+        cmd.args(args);
+        cmd
+    };
+
+    cmd(&["ls", "--all"]).assert().success().stdout(format!(
+        "{}{}",
+        output(fixture.root().join("n")),
+        output(fixture.root().join("n/proj"))
+    ));
+    cmd(&["prune", "--all", "--dry-run"])
+        .assert()
+        .success()
+        .stdout("");
+    cmd(&["prune", "--all"]).assert().success().stdout("");
+    assert!(fixture.root().join("n/proj/cache/deep").exists());
+}
+
+#[test]
+fn prune_refuses_shared_roots() {
+    let mut cmd = Command::cargo_bin("corpus").unwrap();
+    cmd.args(["prune", "--kind", "xdg-data"]).assert().code(2);
+}
+
+#[test]
+fn mv() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo/data")).unwrap();
+    std::fs::create_dir_all(fixture.root().join("taken")).unwrap();
+
+    let from = fixture.source().join("foo");
+    let to = fixture.source().join("bar/baz");
+    fixture
+        .cmd(&["mv", &from.to_string_lossy(), &to.to_string_lossy()])
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("bar/baz")));
+    assert!(fixture.root().join("bar/baz/data").exists());
+    assert!(!fixture.root().join("foo").exists());
+
+    let taken = fixture.source().join("taken");
+    fixture
+        .cmd(&["mv", &to.to_string_lossy(), &taken.to_string_lossy()])
+        .assert()
+        .code(2);
+}
+
+#[test]
+#[cfg(unix)]
+fn adopt_and_unadopt() {
+    let fixture = Fixture::new();
+    let local = fixture.source().join("project/.venv");
+    let entry = fixture.root().join("project");
+    std::fs::create_dir_all(local.join("bin")).unwrap();
+    std::fs::write(local.join("bin/python"), "python").unwrap();
+
+    fixture
+        .cmd_at(&["adopt", ".venv", "--link"], "project")
+        .assert()
+        .success()
+        .stdout(output(entry.clone()));
+    assert_eq!(
+        std::fs::read_to_string(entry.join("bin/python")).unwrap(),
+        "python"
+    );
+    assert_eq!(std::fs::read_link(&local).unwrap(), entry);
+
+    // Adopting again would overwrite the entry.
+    std::fs::remove_file(&local).unwrap();
+    std::fs::create_dir(&local).unwrap();
+    fixture
+        .cmd_at(&["adopt", ".venv"], "project")
+        .assert()
+        .code(2);
+    std::fs::remove_dir(&local).unwrap();
+    std::os::unix::fs::symlink(&entry, &local).unwrap();
+
+    fixture
+        .cmd_at(&["unadopt", ".venv"], "project")
+        .assert()
+        .success()
+        .stdout(output(local.clone()));
+    assert!(!entry.exists());
+    assert!(!std::fs::symlink_metadata(&local)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        std::fs::read_to_string(local.join("bin/python")).unwrap(),
+        "python"
+    );
+}
+
+#[test]
+fn adopt_requires_an_in_tree_path() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.source().join("project")).unwrap();
+
+    fixture
+        .cmd_at(&["adopt", ".venv"], "project")
+        .assert()
+        .code(2);
+    fixture
+        .cmd_at(&["unadopt", ".venv"], "project")
+        .assert()
+        .code(2);
+
+    std::fs::write(fixture.source().join("outside"), "").unwrap();
+    fixture
+        .cmd_at(&["adopt", "../outside"], "project")
+        .assert()
+        .code(2);
+    assert!(fixture.source().join("outside").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn link_and_unlink() {
+    let fixture = Fixture::new();
+    let project = fixture.source().join("project");
+    let entry = fixture.root().join("project");
+    std::fs::create_dir_all(project.join("real")).unwrap();
+
+    fixture
+        .cmd_at(&["link", "--as", ".venv"], "project")
+        .assert()
+        .success()
+        .stdout(output(project.join(".venv")));
+    assert_eq!(std::fs::read_link(project.join(".venv")).unwrap(), entry);
+
+    fixture
+        .cmd_at(&["link", "--as", "real"], "project")
+        .assert()
+        .code(1)
+        .stdout("");
+    assert!(project.join("real").is_dir());
+
+    fixture
+        .cmd(&["unlink", "--all"])
+        .assert()
+        .success()
+        .stdout(output(project.join(".venv")));
+    assert!(std::fs::symlink_metadata(project.join(".venv")).is_err());
+
+    fixture.cmd(&["unlink", "--all"]).assert().code(1);
+}
+
+#[test]
+#[cfg(unix)]
+fn prune_forgets_removed_links() {
+    let fixture = Fixture::new();
+    let project = fixture.source().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    fixture
+        .cmd_at(&["link", "--as", ".venv"], "project")
+        .assert()
+        .success();
+
+    std::fs::remove_dir_all(&project).unwrap();
+    fixture.cmd(&["prune", "--all"]).assert().success();
+    assert_eq!(
+        std::fs::read(fixture.root().join(".corpus-links")).unwrap(),
+        b""
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn exec() {
+    let fixture = Fixture::new();
+    fixture
+        .cmd_at(
+            &[
+                "exec",
+                "--",
+                "sh",
+                "-c",
+                "echo $CORPUS_ENTRY $CORPUS_SOURCE; exit 3",
+            ],
+            "foo",
+        )
+        .assert()
+        .code(3)
+        .stdout(format!(
+            "{} {}\n",
+            fixture.root().join("foo").to_string_lossy(),
+            fixture.source().join("foo").to_string_lossy()
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn exec_nearest() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo")).unwrap();
+
+    fixture
+        .cmd_at(
+            &["exec", "--nearest", "--", "echo", "{entry}", "src={source}"],
+            "foo/bar",
+        )
+        .assert()
+        .success()
+        .stdout(format!(
+            "{} src={}\n",
+            fixture.root().join("foo").display(),
+            fixture.source().join("foo").display()
+        ));
+
+    fixture
+        .cmd_at(
+            &["exec", "--nearest", "--max-depth", "0", "--", "true"],
+            "baz",
+        )
+        .assert()
+        .code(1);
+}
+
+#[test]
+#[cfg(unix)]
+fn exec_ensure_in_entry() {
+    let fixture = Fixture::new();
+    let entry = fixture.root().join("foo/bar");
+
+    fixture
+        .cmd_at(
+            &[
+                "exec",
+                "--nearest",
+                "--max-depth",
+                "1",
+                "--ensure",
+                "--cd",
+                "--",
+                "pwd",
+            ],
+            "foo/bar",
+        )
+        .assert()
+        .success()
+        .stdout(output(entry.clone()));
+    assert!(entry.is_dir());
+}
+
+#[test]
+fn env() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("it's")).unwrap();
+    let (root, source) = (fixture.root().display(), fixture.source().display());
+
+    fixture
+        .cmd_at(&["env"], "it's")
+        .assert()
+        .success()
+        .stdout(format!(
+            "export CORPUS_ENTRY='{root}/it'\\''s'\n\
+             export CORPUS_NEAREST='{root}/it'\\''s'\n\
+             export CORPUS_NEAREST_SOURCE='{source}/it'\\''s'\n\
+             export CORPUS_SOURCE='{source}/it'\\''s'\n\
+             export CORPUS_EXISTS='1'\n",
+            root = root,
+            source = source,
+        ));
+
+    fixture
+        .cmd_at(&["env", "--shell", "fish", "--max-depth", "0"], "new")
+        .assert()
+        .success()
+        .stdout(format!(
+            "set -gx CORPUS_ENTRY '{root}/new'\n\
+             set -e CORPUS_NEAREST\n\
+             set -e CORPUS_NEAREST_SOURCE\n\
+             set -gx CORPUS_SOURCE '{source}/new'\n\
+             set -gx CORPUS_EXISTS '0'\n",
+            root = root,
+            source = source,
+        ));
+
+    fixture
+        .cmd_at(&["env", "--shell", "dotenv"], "it's")
+        .assert()
+        .success()
+        .stdout(format!(
+            "CORPUS_ENTRY=\"{root}/it's\"\n\
+             CORPUS_NEAREST=\"{root}/it's\"\n\
+             CORPUS_NEAREST_SOURCE=\"{source}/it's\"\n\
+             CORPUS_SOURCE=\"{source}/it's\"\n\
+             CORPUS_EXISTS='1'\n",
+            root = root,
+            source = source,
+        ));
+}
+
+#[test]
+fn env_json() {
+    let fixture = Fixture::new();
+    let output = fixture
+        .cmd_at(&["env", "--format", "json", "--max-depth", "0"], "foo")
+        .output()
+        .unwrap();
+
+    let record: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        record["entry"],
+        fixture.root().join("foo").to_string_lossy().as_ref()
+    );
+    assert_eq!(
+        record["source"],
+        fixture.source().join("foo").to_string_lossy().as_ref()
+    );
+    assert_eq!(record["nearest"], serde_json::Value::Null);
+    assert_eq!(record["profile"], serde_json::Value::Null);
+    assert_eq!(record["exists"], false);
+}
+
+#[test]
+fn env_rejects_nul_format() {
+    let fixture = Fixture::new();
+    fixture
+        .cmd_at(&["env", "--format", "nul"], "foo")
+        .assert()
+        .code(2)
+        .stdout("");
+}
+
+#[test]
+fn env_rejects_colliding_profiles() {
+    let fixture = Fixture::new();
+    let assert = fixture
+        .cmd_at(&["env", "a-b", "a.b"], "foo")
+        .assert()
+        .code(2)
+        .stdout("");
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+    assert!(stderr.contains("CORPUS_A_B_"));
+}
+
+#[test]
+fn legacy_flags_are_deprecated() {
+    let fixture = Fixture::new();
+    let assert = fixture
+        .cmd_at(&["--nearest", "--source-path"], "foo")
+        .assert()
+        .success()
+        .stdout(output(fixture.source().to_path_buf()));
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+    assert!(stderr.contains("`--nearest` is deprecated"));
+}
+
+#[test]
+fn format_json() {
+    let fixture = Fixture::new();
+    std::fs::write(fixture.root().join("foo"), "").unwrap();
+
+    let assert = fixture
+        .cmd_at(&["path", "--format", "json"], "foo")
+        .assert()
+        .success();
+    let record: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(
+        record,
+        serde_json::json!({
+            "corpus_path": fixture.root().join("foo"),
+            "source_path": fixture.source().join("foo"),
+            "root": fixture.root(),
+            "relative_to": fixture.source(),
+            "exists": true,
+            "kind": "file",
+        })
+    );
+
+    let assert = fixture
+        .cmd_at(&["path", "--format", "json"], "bar")
+        .assert()
+        .success();
+    let record: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(record["exists"], false);
+    assert_eq!(record["kind"], serde_json::Value::Null);
+
+    // Sources outside of the "relative" directory have none to report.
+    let assert = fixture
+        .cmd(&["-p", "/opt/foo", "path", "--format", "json"])
+        .assert()
+        .success();
+    let record: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+    assert_eq!(record["source_path"], "/opt/foo");
+    assert_eq!(record.get("relative_to"), Some(&serde_json::Value::Null));
+}
+
+#[test]
+fn format_nul() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("foo/bar")).unwrap();
+
+    fixture
+        .cmd_at(&["ls", "--format", "nul", "--sources"], "foo")
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\0{}\0",
+            fixture.source().join("foo").to_string_lossy(),
+            fixture.source().join("foo/bar").to_string_lossy()
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn format_plain_preserves_bytes() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let fixture = Fixture::new();
+    let name = OsStr::from_bytes(b"caf\xe9");
+
+    let mut cmd = fixture.cmd(&["path"]);
+    cmd.arg("-p").arg(fixture.source().join(name));
+
+    let mut expected = fixture.root().join(name).as_os_str().as_bytes().to_vec();
+    expected.push(b'\n');
+    cmd.assert().success().stdout(expected);
+}
+
+#[test]
+fn batch_paths() {
+    let fixture = Fixture::new();
+    let (a, b) = (fixture.source().join("a"), fixture.source().join("b"));
+
+    fixture
+        .cmd(&[
+            "path",
+            "-p",
+            &b.to_string_lossy(),
+            "-p",
+            &a.to_string_lossy(),
+        ])
+        .assert()
+        .success()
+        .stdout(output(fixture.root().join("b")) + &output(fixture.root().join("a")));
+}
+
+#[test]
+fn batch_stdin() {
+    let fixture = Fixture::new();
+    let (a, b) = (fixture.source().join("a"), fixture.source().join("b"));
+    let expected = output(fixture.root().join("a")) + &output(fixture.root().join("b"));
+
+    fixture
+        .cmd(&["path", "--stdin"])
+        .write_stdin(format!("{}\n{}\n", a.display(), b.display()))
+        .assert()
+        .success()
+        .stdout(expected.clone());
+
+    fixture
+        .cmd(&["path", "--stdin"])
+        .write_stdin(format!("{}\0{}\0", a.display(), b.display()))
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn batch_keeps_misses_aligned() {
+    let fixture = Fixture::new();
+    std::fs::create_dir_all(fixture.root().join("a")).unwrap();
+    let (a, b) = (fixture.source().join("a/x"), fixture.source().join("b/x"));
+
+    fixture
+        .cmd(&["nearest", "--stdin", "--max-depth", "1"])
+        .write_stdin(format!("{}\n{}\n", b.display(), a.display()))
+        .assert()
+        .code(1)
+        .stdout(format!("\n{}", output(fixture.root().join("a"))));
+}
+
+#[test]
+fn batch_is_rejected_by_other_commands() {
+    let fixture = Fixture::new();
+
+    fixture.cmd(&["ls", "--stdin"]).assert().code(2);
+}
+
+/// Runs git in `dir`, with an identity to commit as.
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=corpus",
+            "-c",
+            "user.email=corpus@example.com",
+        ])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn git_init() {
+    let fixture = Fixture::new();
+    let work_tree = fixture.source().join("repo");
+    std::fs::create_dir_all(&work_tree).unwrap();
+
+    fixture
+        .cmd_at(&["-e", "git", "git", "init", "--", "--quiet"], "repo")
+        .assert()
+        .success();
+
+    let git_dir = fixture.root().join("repo.git");
+    assert!(git_dir.join("HEAD").is_file());
+    assert!(!work_tree.join(".git").exists());
+
+    // `GIT_DIR` alone is enough to find the work tree.
+    let toplevel = std::process::Command::new("git")
+        .env("GIT_DIR", &git_dir)
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(toplevel.stdout).unwrap(),
+        output(work_tree)
+    );
+}
+
+#[test]
+fn git_clone() {
+    let fixture = Fixture::new();
+    let upstream = tempfile::tempdir().unwrap();
+    git(upstream.path(), &["init", "--quiet", "origin"]);
+    let origin = upstream.path().join("origin");
+    std::fs::write(origin.join("file"), "contents").unwrap();
+    git(&origin, &["add", "file"]);
+    git(&origin, &["commit", "--quiet", "-m", "Add file"]);
+    git(
+        upstream.path(),
+        &["clone", "--quiet", "--bare", "origin", "bare.git"],
+    );
+
+    let bare = upstream.path().join("bare.git");
+    fixture
+        .cmd(&["-e", "git", "git", "clone", &bare.to_string_lossy()])
+        .current_dir(fixture.source())
+        .arg("--")
+        .arg("--quiet")
+        .assert()
+        .success();
+
+    let work_tree = fixture.source().join("bare");
+    let git_dir = fixture.root().join("bare.git");
+    assert_eq!(
+        std::fs::read_to_string(work_tree.join("file")).unwrap(),
+        "contents"
+    );
+    assert!(!work_tree.join(".git").exists());
+
+    let output = std::process::Command::new("git")
+        .current_dir(&work_tree)
+        .env("GIT_DIR", &git_dir)
+        .args(["status", "--porcelain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    fixture
+        .cmd_at(&["-e", "git", "git", "status-env"], "bare/sub/dir")
+        .assert()
+        .success()
+        .stdout(format!(
+            "export GIT_DIR='{}'\nexport GIT_WORK_TREE='{}'\n",
+            git_dir.display(),
+            work_tree.display()
+        ));
+
+    // Cloning again would clobber the git dir.
+    fixture
+        .cmd(&["-e", "git", "git", "clone", &bare.to_string_lossy()])
+        .current_dir(fixture.source())
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn git_status_env_without_repo() {
+    let fixture = Fixture::new();
+
+    fixture
+        .cmd_at(
+            &["-e", "git", "git", "status-env", "--shell", "fish"],
+            "foo",
+        )
+        .assert()
+        .code(1)
+        .stdout("set -e GIT_DIR\nset -e GIT_WORK_TREE\n");
+}